@@ -1,38 +1,40 @@
 use mcgooey::{
     button::Button,
-    column::Column,
     macroquad::{self, prelude::*},
-    text::Text,
-    Geometry, Vector2, View,
+    run, ui, App, View,
 };
 use std::cell::RefCell;
 use std::rc::Rc;
 
 fn main_menu_ui(state: Rc<RefCell<()>>) -> View {
-    View::new(
-        Column::new().push(
-            Button::default(state.clone())
-                .color(WHITE)
-                .is_pressed_callback(|_button: &mut Button<()>| {
+    View::new(ui! {
+        column {
+            button(state) [
+                color(WHITE),
+                is_pressed_callback(|_button: &mut Button<()>| {
                     panic!("EXIT");
-                })
-                .child(
-                    Text::default()
-                        .text("Click me to exit")
-                        .geometry(Geometry::new(Vector2::from(90, 90)))
-                        .color(RED),
-                ),
-        ),
-    )
+                }),
+            ] {
+                text("Click me to exit") [size(90, 90), color(RED)]
+            }
+        }
+    })
 }
 
-#[macroquad::main("XandO")]
-async fn main() {
-    let mut main_menu_ui = main_menu_ui(Rc::new(RefCell::new(())));
-    loop {
-        main_menu_ui.tick();
-        main_menu_ui.draw();
+struct Simple {
+    ui: View,
+}
 
-        next_frame().await
+impl App for Simple {
+    fn view(&mut self) -> &mut View {
+        &mut self.ui
     }
 }
+
+#[macroquad::main("XandO")]
+async fn main() {
+    run(Simple {
+        ui: main_menu_ui(Rc::new(RefCell::new(()))),
+    })
+    .await
+}
@@ -2,8 +2,9 @@ use mcgooey::{
     button::Button,
     column::Column,
     macroquad::{self, prelude::*},
+    run,
     text::Text,
-    Geometry, Vector2, View,
+    App, Geometry, Vector2, View,
 };
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -15,15 +16,11 @@ fn main_menu_ui(state: Rc<RefCell<State>>) -> View {
                 Button::default(state.clone())
                     .geometry(Geometry::new(Vector2::from(40, 40)))
                     .color(WHITE)
+                    .hovered_color(BEIGE)
+                    .pressed_color(GOLD)
                     .is_pressed_callback(|_button: &mut Button<State>| {
                         panic!("EXIT");
                     })
-                    .is_hovered_callback(|button: &mut Button<State>| {
-                        button.set_color(BEIGE);
-                    })
-                    .is_not_hovered_callback(|button: &mut Button<State>| {
-                        button.set_color(WHITE);
-                    })
                     .child(
                         Text::default()
                             .text("Click me to exit")
@@ -35,6 +32,8 @@ fn main_menu_ui(state: Rc<RefCell<State>>) -> View {
                 Button::default(state)
                     .geometry(Geometry::new(Vector2::from(40, 40)))
                     .color(WHITE)
+                    .hovered_color(BEIGE)
+                    .pressed_color(GOLD)
                     .is_pressed_callback(|button: &mut Button<State>| {
                         let temp = button.state.borrow().counter.wrapping_add(5);
                         button.state.borrow_mut().counter = temp;
@@ -53,12 +52,6 @@ fn main_menu_ui(state: Rc<RefCell<State>>) -> View {
                         ));
                         button.set_build(true);
                     })
-                    .is_hovered_callback(|button: &mut Button<State>| {
-                        button.set_color(BEIGE);
-                    })
-                    .is_not_hovered_callback(|button: &mut Button<State>| {
-                        button.set_color(WHITE);
-                    })
                     .child(
                         Text::default()
                             .text("Click me to do nothing")
@@ -72,14 +65,21 @@ fn main_menu_ui(state: Rc<RefCell<State>>) -> View {
 struct State {
     counter: u8,
 }
+struct Test {
+    ui: View,
+}
+
+impl App for Test {
+    fn view(&mut self) -> &mut View {
+        &mut self.ui
+    }
+}
+
 #[macroquad::main("XandO")]
 async fn main() {
     let state = State { counter: 0 };
-    let mut main_menu_ui = main_menu_ui(Rc::new(RefCell::new(state)));
-    loop {
-        main_menu_ui.tick();
-        main_menu_ui.draw();
-
-        next_frame().await
-    }
+    run(Test {
+        ui: main_menu_ui(Rc::new(RefCell::new(state))),
+    })
+    .await
 }
@@ -1,5 +1,6 @@
 use mcgooey::macroquad::prelude::*;
-use mcgooey::{button::Button, column::Column, row::Row, text::Text, Geometry, Vector2, View};
+use mcgooey::theme::{Style, Theme};
+use mcgooey::{button::Button, grid::Grid, text::Text, Geometry, Vector2, View};
 use std::{cell::RefCell, rc::Rc};
 
 #[derive(Clone, PartialEq, Debug)]
@@ -176,54 +177,42 @@ impl GameState {
 }
 
 pub fn game_ui(state: Rc<RefCell<GameState>>) -> View {
-    //represents each horizontal line of cells
-    let mut column = Column::new();
-
-    //calculate the precentage width and height to be given to each cell, as a part in the whole 100% of width or height
-    let side_percentage = 100 / state.borrow().side_length;
-
-    //add buttons to rows and rows to the column
-    for i in 0..state.borrow().side_length {
-        let mut row =
-            Row::new().geometry(Geometry::new(Vector2::new(100f32, side_percentage as f32)));
-        for j in 0..state.borrow().side_length {
-            row = row.push(
-                Button::default(state.clone())
-                    .id((i * state.borrow().side_length + j) as u16)
-                    .color(BEIGE)
-                    .geometry(Geometry::new(Vector2::new(
-                        (side_percentage - 10) as f32,
-                        90f32,
-                    )))
-                    .is_hovered_callback(|button: &mut Button<GameState>| {
-                        button.set_color(RED);
-                    })
-                    .is_not_hovered_callback(|button: &mut Button<GameState>| {
-                        button.set_color(BEIGE);
-                    })
-                    .is_pressed_callback(|button: &mut Button<GameState>| {
-                        button.set_child(Box::new(
-                            Text::default()
-                                .text((|| {
-                                    {
-                                        let mut state = button.state.borrow_mut();
-                                        state.toggle_player();
-                                        state.cells[button.id as usize] = state.curr_player.clone();
-                                    }
-                                    //use the opposite player because we just toggled
-                                    if button.state.borrow().curr_player == PlayerState::X {
-                                        "X"
-                                    } else {
-                                        "Y"
-                                    }
-                                })())
-                                .geometry(Geometry::new(Vector2::from(80, 80))),
-                        ));
-                        button.set_build(true);
-                    }),
-            );
-        }
-        column = column.push(row);
+    let side_length = state.borrow().side_length;
+    //one cell for each button, all cells sized equally
+    let mut grid = Grid::new(side_length, side_length);
+
+    for i in 0..side_length * side_length {
+        grid = grid.push(
+            Button::default(state.clone())
+                .id(i as u16)
+                .geometry(Geometry::new(Vector2::from(90, 90)))
+                .is_pressed_callback(move |button: &mut Button<GameState>| {
+                    button.set_child(Box::new(
+                        Text::default()
+                            .text((|| {
+                                {
+                                    let mut state = button.state.borrow_mut();
+                                    state.toggle_player();
+                                    state.cells[i] = state.curr_player.clone();
+                                }
+                                //use the opposite player because we just toggled
+                                if button.state.borrow().curr_player == PlayerState::X {
+                                    "X"
+                                } else {
+                                    "Y"
+                                }
+                            })())
+                            .geometry(Geometry::new(Vector2::from(80, 80))),
+                    ));
+                    button.set_build(true);
+                }),
+        );
     }
-    View::new(column)
+    View::new(grid).theme(Theme {
+        button: Style {
+            hovered: RED,
+            ..Style::plain(BEIGE, WHITE)
+        },
+        ..Theme::default()
+    })
 }
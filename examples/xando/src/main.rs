@@ -4,69 +4,95 @@ mod game;
 use game::{game_ui, GameState};
 use mcgooey::button::Button;
 use mcgooey::column::Column;
+use mcgooey::focus::Direction;
 use mcgooey::macroquad::{self, prelude::*};
+use mcgooey::router::{Router, RouterHandle, Transition, TransitionKind};
 use mcgooey::text::Text;
-use mcgooey::{Geometry, Vector2, View};
+use mcgooey::theme::{Style, Theme};
+use mcgooey::{run, App, Geometry, Vector2, View};
 
-#[derive(PartialEq, Clone, Copy)]
-///Represents different UI sections
-enum UIMode {
-    MainMenu,
-    Game,
-}
-
-struct State {
-    pub mode: UIMode,
-}
-
-fn main_menu_ui(state: Rc<RefCell<State>>) -> View {
+fn main_menu_ui(router: RouterHandle) -> View {
+    let theme = Theme {
+        button: Style {
+            hovered: BEIGE,
+            ..Style::plain(WHITE, RED)
+        },
+        ..Theme::default()
+    };
     View::new(
         Column::new().push(
-            Button::default(state)
+            Button::default(Rc::new(RefCell::new(router)))
                 .geometry(Geometry::new(Vector2::from(90, 40)))
-                .color(WHITE)
-                .is_pressed_callback(|button: &mut Button<State>| {
-                    button.state.borrow_mut().mode = UIMode::Game;
-                })
-                .is_hovered_callback(|button: &mut Button<State>| {
-                    button.set_color(BEIGE);
-                })
-                .is_not_hovered_callback(|button: &mut Button<State>| {
-                    button.set_color(WHITE);
+                .is_pressed_callback(|button: &mut Button<RouterHandle>| {
+                    button.state.borrow().push("game");
                 })
                 .child(
                     Text::default()
                         .text("Click here to start playing")
-                        .geometry(Geometry::new(Vector2::from(90, 90)))
-                        .color(RED),
+                        .geometry(Geometry::new(Vector2::from(90, 90))),
                 ),
         ),
     )
+    .theme(theme)
 }
 
-#[macroquad::main("XandO")]
-async fn main() {
-    loop {
-        let main_menu_state = Rc::new(RefCell::new(State {
-            mode: UIMode::MainMenu,
-        }));
+struct Xando {
+    router: Router,
+    ///state of the game being played, set every time the game route is pushed
+    game: Rc<RefCell<Option<Rc<RefCell<GameState>>>>>,
+}
 
-        let mut main_menu_ui = main_menu_ui(main_menu_state.clone());
-        while main_menu_state.borrow().mode == UIMode::MainMenu {
-            main_menu_ui.tick();
-            main_menu_ui.draw();
+impl Xando {
+    fn new() -> Self {
+        let router = Router::new();
+        let handle = router.handle();
+        let game = Rc::new(RefCell::new(None));
+        let current_game = game.clone();
+        let mut router = router
+            .route("main_menu", move || main_menu_ui(handle.clone()))
+            //a new game starts every time the main menu is left
+            .route("game", move || {
+                let game_state = Rc::new(RefCell::new(GameState::new(3)));
+                *current_game.borrow_mut() = Some(game_state.clone());
+                game_ui(game_state)
+            })
+            .transition(Transition::new(
+                TransitionKind::Slide(Direction::Left),
+                0.3f32,
+            ));
+        router.push("main_menu");
+        Xando { router, game }
+    }
+}
 
-            next_frame().await
+impl App for Xando {
+    fn update(&mut self, dt: f32) {
+        self.router.update(dt);
+        let over = match &*self.game.borrow() {
+            Some(game_state) => game_state.borrow_mut().tick(),
+            None => false,
+        };
+        if over {
+            *self.game.borrow_mut() = None;
+            self.router.pop();
         }
+    }
 
-        let game_state = Rc::new(RefCell::new(GameState::new(3)));
-        let mut game_ui = game_ui(game_state.clone());
+    fn view(&mut self) -> &mut View {
+        self.router.view()
+    }
 
-        while !game_state.borrow_mut().tick() {
-            game_ui.tick();
-            game_ui.draw();
+    fn draw(&mut self) {
+        self.router.draw();
+    }
 
-            next_frame().await
-        }
+    //the back button on Android asks to quit, go back from the game to the menu instead
+    fn on_quit_requested(&mut self) -> bool {
+        !cfg!(target_os = "android") || !self.router.back()
     }
 }
+
+#[macroquad::main("XandO")]
+async fn main() {
+    run(Xando::new()).await
+}
@@ -0,0 +1,270 @@
+/*
+Animation helpers. Easing curves map linear progress through an animation, from 0 to 1, to how far along the animated
+value should be, so that motion can speed up and slow down instead of moving at a constant rate.
+
+Tweens move a widget property from one value to another over time. Widgets that can be animated keep their running
+tweens in an Animations, started from their animate_* methods, usually in a callback. View::tick advances them every
+frame, and rebuilds the tree when a tween changes a widget's geometry.
+*/
+
+use super::{Directions2D, Geometry, Vector2};
+use macroquad::prelude::Color;
+
+///Shape of the motion of an animation
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum Easing {
+    ///Constant speed
+    Linear,
+    ///Start slow and speed up
+    EaseIn,
+    ///Start fast and slow down
+    EaseOut,
+    ///Start slow, speed up, and slow down again at the end
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    ///How far along the animated value is at progress t, both from 0 to 1. t is clamped to that range.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0f32, 1f32);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1f32 - (1f32 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5f32 {
+                    4f32 * t * t * t
+                } else {
+                    1f32 - (-2f32 * t + 2f32).powi(3) / 2f32
+                }
+            }
+        }
+    }
+}
+
+///Values that can be interpolated
+pub trait Lerp: Copy {
+    ///The value t of the way from self to to, where t is 0 at self and 1 at to
+    fn lerp(self, to: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for Vector2 {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        Vector2::new(self.x.lerp(to.x, t), self.y.lerp(to.y, t))
+    }
+}
+
+impl Lerp for Directions2D {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        Directions2D::new(
+            self.top.lerp(to.top, t),
+            self.bottom.lerp(to.bottom, t),
+            self.left.lerp(to.left, t),
+            self.right.lerp(to.right, t),
+        )
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        Color::new(
+            self.r.lerp(to.r, t),
+            self.g.lerp(to.g, t),
+            self.b.lerp(to.b, t),
+            self.a.lerp(to.a, t),
+        )
+    }
+}
+
+///Moves a value from one end to another over duration seconds
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    duration: f32,
+    easing: Easing,
+    ///seconds since the tween started
+    elapsed: f32,
+}
+
+impl<T: Lerp> Tween<T> {
+    pub fn new(from: T, to: T, duration: f32, easing: Easing) -> Self {
+        Tween {
+            from,
+            to,
+            duration,
+            easing,
+            elapsed: 0f32,
+        }
+    }
+
+    ///Move the tween dt seconds further along, and return its value
+    pub fn advance(&mut self, dt: f32) -> T {
+        self.elapsed += dt;
+        self.value()
+    }
+
+    ///Value the tween is at
+    pub fn value(&self) -> T {
+        if self.is_finished() {
+            return self.to;
+        }
+        self.from
+            .lerp(self.to, self.easing.apply(self.elapsed / self.duration))
+    }
+
+    ///Whether the tween reached its end
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+///Tweens running on the animatable properties of a widget. Starting a tween on a property replaces the one running
+///on it, starting from wherever that one got to.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Animations {
+    pub color: Option<Tween<Color>>,
+    pub sides: Option<Tween<Vector2>>,
+    pub margins: Option<Tween<Directions2D>>,
+    pub opacity: Option<Tween<f32>>,
+}
+
+impl Animations {
+    ///Advance every running tween by dt seconds, writing their values to the widget's properties. Finished tweens are
+    ///removed. Returns true if the geometry changed, so that the widget can ask to be rebuilt.
+    pub fn advance(
+        &mut self,
+        dt: f32,
+        color: &mut Color,
+        geometry: &mut Geometry,
+        opacity: &mut f32,
+    ) -> bool {
+        step(&mut self.color, color, dt);
+        step(&mut self.opacity, opacity, dt);
+        let sides = step(&mut self.sides, &mut geometry.sides, dt);
+        let margins = step(&mut self.margins, &mut geometry.margins, dt);
+        sides || margins
+    }
+
+    ///Whether any tween is running
+    pub fn is_running(&self) -> bool {
+        self.color.is_some()
+            || self.sides.is_some()
+            || self.margins.is_some()
+            || self.opacity.is_some()
+    }
+}
+
+///Advance tween by dt and write its value to value, removing it once finished. Returns true if a tween was running.
+fn step<T: Lerp>(tween: &mut Option<Tween<T>>, value: &mut T, dt: f32) -> bool {
+    match tween {
+        Some(running) => {
+            *value = running.advance(dt);
+            if running.is_finished() {
+                *tween = None;
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::button::Button;
+    use crate::column::Column;
+    use crate::event::Event;
+    use crate::render::RecordingRenderer;
+    use crate::View;
+    use macroquad::prelude::{BLACK, WHITE};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn tweens_ease_and_finish() {
+        let mut tween = Tween::new(0f32, 10f32, 2f32, Easing::EaseIn);
+        assert_eq!(tween.advance(1f32), 1.25f32);
+        assert!(!tween.is_finished());
+        assert_eq!(tween.advance(5f32), 10f32);
+        assert!(tween.is_finished());
+
+        let gray = Tween::new(BLACK, WHITE, 1f32, Easing::Linear).advance(0.5f32);
+        assert_eq!((gray.r, gray.a), (0.5f32, 1f32));
+    }
+
+    #[test]
+    fn hovering_grows_button_and_rebuilds() {
+        let renderer = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        let mut view = View::new(
+            Column::new().push(
+                Button::default(Rc::new(RefCell::new(())))
+                    .geometry(Geometry::new(Vector2::from(50, 50)))
+                    .is_hovered_callback(|button: &mut Button<()>| {
+                        button.animate_sides(Vector2::from(100, 100), 1f32, Easing::Linear);
+                        button.animate_opacity(0f32, 1f32, Easing::Linear);
+                    }),
+            ),
+        );
+        view.build_with(&renderer);
+        let sides = |view: &View| view.root().child(0).unwrap().get_geometry().abs_sides;
+        assert_eq!(sides(&view), Vector2::new(400f32, 300f32));
+
+        view.handle_event(&Event::PointerMove {
+            position: Vector2::new(400f32, 300f32),
+        });
+        view.animate(0.5f32);
+        assert!(view.root().get_build());
+        view.build_with(&renderer);
+        assert!(!view.root().get_build());
+        assert_eq!(sides(&view), Vector2::new(600f32, 450f32));
+
+        view.animate(1f32);
+        view.build_with(&renderer);
+        assert_eq!(sides(&view), Vector2::new(800f32, 600f32));
+        //the tweens are done, so nothing asks for another rebuild
+        view.animate(1f32);
+        assert!(!view.root().get_build());
+    }
+
+    #[test]
+    fn fading_color_gives_way_to_the_state_fill() {
+        let mut view = View::new(
+            Column::new().push(
+                Button::default(Rc::new(RefCell::new(())))
+                    .geometry(Geometry::new(Vector2::from(50, 50)))
+                    .key("button")
+                    .hovered_color(BLACK)
+                    .is_hovered_callback(|button: &mut Button<()>| {
+                        button.animate_color(BLACK, 1f32, Easing::Linear);
+                    }),
+            ),
+        );
+        view.build_with(&RecordingRenderer::new(Vector2::new(800f32, 600f32)));
+        let color = |view: &View| {
+            let button = view.find_by_key("button").unwrap();
+            button.downcast_ref::<Button<()>>().unwrap().get_color()
+        };
+        let hover = |view: &mut View, x: f32| {
+            view.handle_event(&Event::PointerMove {
+                position: Vector2::new(x, 300f32),
+            });
+        };
+
+        hover(&mut view, 400f32);
+        view.animate(0.5f32);
+        assert_eq!(color(&view).r, 0.5f32);
+        view.animate(1f32);
+        assert_eq!(color(&view), BLACK);
+        //the fade didn't replace the fill of the other states
+        hover(&mut view, 10f32);
+        assert_eq!(color(&view), WHITE);
+    }
+}
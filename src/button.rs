@@ -1,20 +1,68 @@
+use super::animation::{Animations, Easing, Tween};
+use super::event::Event;
+use super::font::FontHandle;
+use super::key::Key;
+use super::layout::{BuildContext, LayoutError};
+use super::render::{Renderer, Transform, TransformedRenderer};
+use super::theme::{Style, Theme};
 use super::{Directions2D, Geometry, Vector2, Widget};
 use macroquad::prelude::*;
+use std::any::Any;
 use std::cell::RefCell;
 use std::rc::Rc;
+
+///Callback invoked by a button on itself. Can capture anything it needs from its environment.
+pub type Callback<T> = Box<dyn FnMut(&mut Button<T>)>;
+
+///State a button is drawn in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ButtonState {
+    Normal,
+    ///the pointer is over the button
+    Hovered,
+    ///the pointer went down on the button and is still held over it
+    Pressed,
+    ///the button ignores presses
+    Disabled,
+}
+
 ///Stores coordinate and size of each button. X and Y are the top left coordinates of each button
 pub struct Button<T> {
     //set by user
     geometry: Geometry,
-    ///default color of button
-    color: Color,
+    ///fill in every state without a color of its own, instead of the theme's
+    color: Option<Color>,
+    ///fill while hovered, pressed and disabled, instead of color or the theme's
+    hovered_color: Option<Color>,
+    pressed_color: Option<Color>,
+    disabled_color: Option<Color>,
+    ///radius of the corners instead of the theme's
+    corner_radius: Option<f32>,
+    ///color of texts inside the button in every state without a text color of its own, instead of the theme's
+    text_color: Option<Color>,
+    ///color of texts inside the button while hovered, pressed and disabled, instead of text_color or the theme's
+    hovered_text_color: Option<Color>,
+    pressed_text_color: Option<Color>,
+    disabled_text_color: Option<Color>,
+    ///font of texts inside the button instead of the theme's
+    font: Option<FontHandle>,
+    ///style taken from the View's theme in the last build
+    style: Style,
+    ///multiplies the alpha of the button and its child, from 0 to 1
+    opacity: f32,
+    ///tweens running on the button's properties
+    animations: Animations,
+    ///fill of the running color tween, drawn instead of the fill of the button's state until the tween finishes
+    animated_color: Option<Color>,
     //optional child widget
     child: Option<Box<dyn Widget>>,
+    //callbacks are None only while they are running, see Button::call
     //called when mouse/finger enters hover over a button
-    is_hovered_callback: fn(&mut Button<T>),
+    is_hovered_callback: Option<Callback<T>>,
     //called when mouse/finger leaves hover over the button
-    is_not_hovered_callback: fn(&mut Button<T>),
-    is_pressed_callback: fn(&mut Button<T>),
+    is_not_hovered_callback: Option<Callback<T>>,
+    //called when a press on the button is released over it, or it is activated with the keyboard or a gamepad
+    is_pressed_callback: Option<Callback<T>>,
     is_disabled: bool,
 
     ///should the widget be rebuilt?
@@ -22,7 +70,11 @@ pub struct Button<T> {
 
     ///wether or not the button is being hovered
     is_hovered: bool,
+    ///whether the pointer went down on the button and is still held over it
+    is_pressed: bool,
     pub id: u16,
+    ///identifies the widget within its View, see Widget::get_key
+    key: Option<Key>,
     pub state: Rc<RefCell<T>>,
 }
 
@@ -30,14 +82,29 @@ impl<T> Button<T> {
     pub fn default(state: Rc<RefCell<T>>) -> Self {
         Button {
             geometry: Geometry::new(Vector2::new(100f32, 100f32)),
-            color: WHITE,
+            color: None,
+            hovered_color: None,
+            pressed_color: None,
+            disabled_color: None,
+            corner_radius: None,
+            text_color: None,
+            hovered_text_color: None,
+            pressed_text_color: None,
+            disabled_text_color: None,
+            font: None,
+            style: Theme::default().button,
+            opacity: 1f32,
+            animations: Animations::default(),
+            animated_color: None,
             child: None,
-            is_hovered_callback: |_: &mut Button<T>| {},
-            is_not_hovered_callback: |_: &mut Button<T>| {},
-            is_pressed_callback: |_: &mut Button<T>| {},
+            is_hovered_callback: Some(Box::new(|_: &mut Button<T>| {})),
+            is_not_hovered_callback: Some(Box::new(|_: &mut Button<T>| {})),
+            is_pressed_callback: Some(Box::new(|_: &mut Button<T>| {})),
             is_disabled: false,
             is_hovered: false,
+            is_pressed: false,
             id: 0,
+            key: None,
             state,
             build: false,
         }
@@ -52,26 +119,110 @@ impl<T> Button<T> {
     }
 
     pub fn color(self, color: Color) -> Self {
-        Button { color, ..self }
+        Button {
+            color: Some(color),
+            ..self
+        }
+    }
+
+    pub fn hovered_color(self, hovered_color: Color) -> Self {
+        Button {
+            hovered_color: Some(hovered_color),
+            ..self
+        }
+    }
+
+    pub fn pressed_color(self, pressed_color: Color) -> Self {
+        Button {
+            pressed_color: Some(pressed_color),
+            ..self
+        }
+    }
+
+    pub fn disabled_color(self, disabled_color: Color) -> Self {
+        Button {
+            disabled_color: Some(disabled_color),
+            ..self
+        }
+    }
+
+    pub fn corner_radius(self, corner_radius: f32) -> Self {
+        Button {
+            corner_radius: Some(corner_radius),
+            ..self
+        }
+    }
+
+    ///Color of the texts inside the button
+    pub fn text_color(self, text_color: Color) -> Self {
+        Button {
+            text_color: Some(text_color),
+            ..self
+        }
+    }
+
+    ///Color of the texts inside the button while it is hovered
+    pub fn hovered_text_color(self, hovered_text_color: Color) -> Self {
+        Button {
+            hovered_text_color: Some(hovered_text_color),
+            ..self
+        }
+    }
+
+    ///Color of the texts inside the button while it is pressed
+    pub fn pressed_text_color(self, pressed_text_color: Color) -> Self {
+        Button {
+            pressed_text_color: Some(pressed_text_color),
+            ..self
+        }
+    }
+
+    ///Color of the texts inside the button while it is disabled
+    pub fn disabled_text_color(self, disabled_text_color: Color) -> Self {
+        Button {
+            disabled_text_color: Some(disabled_text_color),
+            ..self
+        }
+    }
+
+    ///Font from the View's font registry to draw the texts inside the button with
+    pub fn font(self, font: FontHandle) -> Self {
+        Button {
+            font: Some(font),
+            ..self
+        }
+    }
+
+    pub fn opacity(self, opacity: f32) -> Self {
+        Button { opacity, ..self }
     }
 
-    pub fn is_hovered_callback(self, is_hovered_callback: fn(&mut Button<T>)) -> Self {
+    pub fn is_hovered_callback<F: FnMut(&mut Button<T>) + 'static>(
+        self,
+        is_hovered_callback: F,
+    ) -> Self {
         Button {
-            is_hovered_callback,
+            is_hovered_callback: Some(Box::new(is_hovered_callback)),
             ..self
         }
     }
 
-    pub fn is_not_hovered_callback(self, is_not_hovered_callback: fn(&mut Button<T>)) -> Self {
+    pub fn is_not_hovered_callback<F: FnMut(&mut Button<T>) + 'static>(
+        self,
+        is_not_hovered_callback: F,
+    ) -> Self {
         Button {
-            is_not_hovered_callback,
+            is_not_hovered_callback: Some(Box::new(is_not_hovered_callback)),
             ..self
         }
     }
 
-    pub fn is_pressed_callback(self, is_pressed_callback: fn(&mut Button<T>)) -> Self {
+    pub fn is_pressed_callback<F: FnMut(&mut Button<T>) + 'static>(
+        self,
+        is_pressed_callback: F,
+    ) -> Self {
         Button {
-            is_pressed_callback,
+            is_pressed_callback: Some(Box::new(is_pressed_callback)),
             ..self
         }
     }
@@ -87,71 +238,37 @@ impl<T> Button<T> {
         Self { id, ..self }
     }
 
-    fn handle_input(&mut self) {
-        //handle touch input first
-        for touch in touches() {
-            let x = touch.position.x;
-            let y = touch.position.y;
-            match touch.phase {
-                TouchPhase::Stationary | TouchPhase::Started | TouchPhase::Moved => {
-                    //check if touch intersects the button, call callbacks if user enters or leaves the hover state
-                    if self.geometry.top_left.x < x
-                        && x < self.geometry.top_left.x + self.geometry.abs_sides.x
-                        && self.geometry.top_left.y < y
-                        && y < self.geometry.top_left.y + self.geometry.abs_sides.y
-                    {
-                        if !self.is_hovered {
-                            (self.is_hovered_callback)(self);
-                            self.is_hovered = true;
-                        }
-                    } else {
-                        if self.is_hovered {
-                            (self.is_not_hovered_callback);
-                            self.is_hovered = false;
-                        }
-                    }
-                }
-                TouchPhase::Ended => {
-                    //check if touch intersects any button, call touched callback if user touches
-                    if self.geometry.top_left.x < x
-                        && x < self.geometry.top_left.x + self.geometry.abs_sides.x
-                        && self.geometry.top_left.y < y
-                        && y < self.geometry.top_left.y + self.geometry.abs_sides.y
-                    {
-                        if !self.is_disabled {
-                            (self.is_pressed_callback)(self);
-                        }
-                    }
-                }
-                _ => {}
-            }
+    ///Key identifying the widget within its View. Must be unique.
+    pub fn key<K: Into<Key>>(self, key: K) -> Self {
+        Self {
+            key: Some(key.into()),
+            ..self
         }
+    }
 
-        //handle mouse input
-        let (x, y) = mouse_position();
-        //check if mouse is inside any button
-        if self.geometry.top_left.x < x
-            && x < self.geometry.top_left.x + self.geometry.abs_sides.x
-            && self.geometry.top_left.y < y
-            && y < self.geometry.top_left.y + self.geometry.abs_sides.y
-        {
-            if is_mouse_button_pressed(MouseButton::Left) && !self.is_disabled {
-                (self.is_pressed_callback)(self);
-            } else {
-                if !self.is_hovered {
-                    (self.is_hovered_callback)(self);
-                    self.is_hovered = true;
-                }
+    ///Run the callback selected by slot. The callback is taken out of the button while it runs so that it can be
+    ///handed the button mutably, and put back afterwards unless it replaced itself.
+    fn call(&mut self, slot: fn(&mut Button<T>) -> &mut Option<Callback<T>>) {
+        if let Some(mut callback) = slot(self).take() {
+            callback(self);
+            let slot = slot(self);
+            if slot.is_none() {
+                *slot = Some(callback);
             }
-        } else if self.is_hovered {
-            (self.is_not_hovered_callback)(self);
-            self.is_hovered = false;
+        }
+    }
+
+    ///Rebuild if the text color changed since it was text_color, so that texts inside the button are drawn in the
+    ///color of its new state
+    fn restyle(&mut self, text_color: Color) {
+        if self.get_text_color() != text_color {
+            self.build = true;
         }
     }
 
     #[cfg(feature = "debug_draw")]
-    fn debug_draw(&self) {
-        draw_rectangle_lines(
+    fn debug_draw(&self, renderer: &mut dyn Renderer) {
+        renderer.draw_rectangle_lines(
             self.geometry.top_left.x - self.geometry.abs_margins.left,
             self.geometry.top_left.y - self.geometry.abs_margins.top,
             self.geometry.abs_sides.x
@@ -163,7 +280,7 @@ impl<T> Button<T> {
             1.0,
             MAGENTA,
         );
-        draw_circle(
+        renderer.draw_circle(
             self.geometry.top_left.x,
             self.geometry.top_left.y,
             5f32,
@@ -172,27 +289,42 @@ impl<T> Button<T> {
     }
 }
 
-impl<T> Widget for Button<T> {
-    fn draw(&self) {
+impl<T: 'static> Widget for Button<T> {
+    fn draw(&self, renderer: &mut dyn Renderer) {
         #[cfg(feature = "debug_draw")]
-        self.debug_draw();
-        draw_rectangle(
+        self.debug_draw(renderer);
+        let renderer = &mut TransformedRenderer::new(
+            renderer,
+            Transform {
+                alpha: self.opacity,
+                ..Transform::default()
+            },
+        );
+        renderer.draw_rounded_rectangle(
             self.geometry.top_left.x,
             self.geometry.top_left.y,
             self.geometry.abs_sides.x,
             self.geometry.abs_sides.y,
-            self.color,
+            self.corner_radius.unwrap_or(self.style.corner_radius),
+            self.get_color(),
         );
         if let Some(widget) = &self.child {
-            widget.draw();
+            widget.draw(renderer);
         }
     }
-    fn build(&mut self, geometry: &Geometry, margins: Option<Directions2D>) -> Vector2 {
+    fn build(
+        &mut self,
+        geometry: &Geometry,
+        margins: Option<Directions2D>,
+        ctx: &mut BuildContext,
+    ) -> Result<Vector2, LayoutError> {
+        self.build = false;
+        let sides = self.geometry.validate(self.id, ctx)?;
         //Find original dimensions of parent and
         //calculate dimensions of this widget from parent dimensions and positions from margins
         let dimensions = Vector2 {
-            x: geometry.abs_sides.x * self.geometry.sides.x / 100f32,
-            y: geometry.abs_sides.y * self.geometry.sides.y / 100f32,
+            x: geometry.abs_sides.x * sides.x / 100f32,
+            y: geometry.abs_sides.y * sides.y / 100f32,
         };
         self.geometry.abs_sides = dimensions;
         let margins = margins.unwrap_or(Directions2D {
@@ -211,28 +343,151 @@ impl<T> Widget for Button<T> {
         self.geometry.top_left = tl;
         self.geometry.top_left_curr = self.geometry.top_left;
 
-        if let Some(widget) = &mut self.child {
-            widget.build(&self.geometry, None);
-        }
+        self.style = ctx.theme.button;
+        //texts inside the button are drawn in its text color and font
+        let theme = ctx.theme;
+        ctx.theme.text = Style {
+            text_color: self.get_text_color(),
+            font: self.font.or(self.style.font),
+            ..theme.text
+        };
+        let built = match &mut self.child {
+            Some(widget) => widget.build(&self.geometry, None, ctx).map(|_| ()),
+            None => Ok(()),
+        };
+        ctx.theme = theme;
+        built?;
         //return TL offsetted
-        Vector2 {
+        Ok(Vector2 {
             x: geometry.top_left_curr.x + margins.left + dimensions.x + margins.right,
             y: geometry.top_left_curr.y + margins.top + dimensions.y + margins.bottom,
-        }
+        })
     }
 
     fn tick(&mut self) {
-        self.handle_input();
+        if let Some(widget) = &mut self.child {
+            widget.tick();
+        }
+    }
+
+    fn animate(&mut self, dt: f32) {
+        let mut color = self.get_color();
+        if self
+            .animations
+            .advance(dt, &mut color, &mut self.geometry, &mut self.opacity)
+        {
+            self.build = true;
+        }
+        //once the fade is over the fill of the state shows again
+        self.animated_color = self.animations.color.map(|_| color);
+    }
+
+    fn handle_event(&mut self, event: &Event) -> bool {
+        if let Some(widget) = &mut self.child {
+            if widget.handle_event(event) {
+                return true;
+            }
+        }
+        let text_color = self.get_text_color();
+        let handled = match *event {
+            //check if the pointer is over the button, call callbacks if user enters or leaves the hover state.
+            //Movement is never marked handled, so that overlapping widgets can track hovering too.
+            Event::PointerMove { position } => {
+                if self.geometry.contains(position) {
+                    if !self.is_hovered {
+                        Button::call(self, |button| &mut button.is_hovered_callback);
+                        self.is_hovered = true;
+                    }
+                } else {
+                    //dragging off the button cancels the press
+                    self.is_pressed = false;
+                    if self.is_hovered {
+                        Button::call(self, |button| &mut button.is_not_hovered_callback);
+                        self.is_hovered = false;
+                    }
+                }
+                false
+            }
+            //touches reach the button through macroquad simulating the mouse with touch
+            Event::PointerDown {
+                position,
+                button: MouseButton::Left,
+            } if self.geometry.contains(position) => {
+                self.is_pressed = !self.is_disabled;
+                true
+            }
+            //the press only counts if the pointer is released over the button
+            Event::PointerUp {
+                position,
+                button: MouseButton::Left,
+            } if self.is_pressed => {
+                self.is_pressed = false;
+                if self.geometry.contains(position) && !self.is_disabled {
+                    Button::call(self, |button| &mut button.is_pressed_callback);
+                }
+                true
+            }
+            _ => false,
+        };
+        self.restyle(text_color);
+        handled
     }
 
     fn get_side(&self) -> Vector2 {
         self.geometry.sides
     }
 
+    fn get_geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+
+    fn kind(&self) -> &'static str {
+        "button"
+    }
+
+    fn child_count(&self) -> usize {
+        self.child.iter().count()
+    }
+
+    fn child(&self, index: usize) -> Option<&dyn Widget> {
+        match index {
+            0 => self.child.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn child_mut(&mut self, index: usize) -> Option<&mut dyn Widget> {
+        match (index, &mut self.child) {
+            (0, Some(child)) => Some(child.as_mut()),
+            _ => None,
+        }
+    }
+
+    fn is_focusable(&self) -> bool {
+        !self.is_disabled
+    }
+
+    fn restore_state(&mut self, old: &dyn Widget) {
+        if let Some(old) = old.downcast_ref::<Button<T>>() {
+            self.is_hovered = old.is_hovered;
+            self.is_disabled = old.is_disabled;
+        }
+    }
+
+    fn activate(&mut self) {
+        if !self.is_disabled {
+            Button::call(self, |button| &mut button.is_pressed_callback);
+        }
+    }
+
     fn get_id(&self) -> u16 {
         self.id
     }
 
+    fn get_key(&self) -> Option<&Key> {
+        self.key.as_ref()
+    }
+
     fn get_build(&self) -> bool {
         if self.build {
             true
@@ -242,12 +497,25 @@ impl<T> Widget for Button<T> {
             false
         }
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 //Setters and getters for Button
 impl<T> Button<T> {
     pub fn set_color(&mut self, color: Color) {
-        self.color = color;
+        self.color = Some(color);
+    }
+
+    ///Go back to filling the button with the theme's colors
+    pub fn clear_color(&mut self) {
+        self.color = None;
     }
 
     pub fn set_child(&mut self, child: Box<dyn Widget>) {
@@ -255,30 +523,237 @@ impl<T> Button<T> {
     }
 
     pub fn set_is_disabled(&mut self, is_disabled: bool) {
+        let text_color = self.get_text_color();
         self.is_disabled = is_disabled;
+        self.is_pressed = self.is_pressed && !is_disabled;
+        self.restyle(text_color);
     }
 
     pub fn set_geometry(&mut self, geometry: Geometry) {
         self.geometry = geometry;
     }
 
-    pub fn set_is_hovered_callback(&mut self, is_hovered_callback: fn(&mut Button<T>)) {
-        self.is_hovered_callback = is_hovered_callback;
+    pub fn set_is_hovered_callback<F: FnMut(&mut Button<T>) + 'static>(
+        &mut self,
+        is_hovered_callback: F,
+    ) {
+        self.is_hovered_callback = Some(Box::new(is_hovered_callback));
+    }
+
+    pub fn set_is_not_hovered_callback<F: FnMut(&mut Button<T>) + 'static>(
+        &mut self,
+        is_not_hovered_callback: F,
+    ) {
+        self.is_not_hovered_callback = Some(Box::new(is_not_hovered_callback));
+    }
+
+    pub fn set_is_pressed_callback<F: FnMut(&mut Button<T>) + 'static>(
+        &mut self,
+        is_pressed_callback: F,
+    ) {
+        self.is_pressed_callback = Some(Box::new(is_pressed_callback));
     }
 
-    pub fn set_is_not_hovered_callback(&mut self, is_not_hovered_callback: fn(&mut Button<T>)) {
-        self.is_not_hovered_callback = is_not_hovered_callback;
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity;
     }
 
-    pub fn set_is_pressed_callback(&mut self, is_pressed_callback: fn(&mut Button<T>)) {
-        self.is_pressed_callback = is_pressed_callback;
+    ///Fade the fill from where it is to color over duration seconds. When the fade is over, the button is filled with the
+    ///color of its state again, so fade to that color, like hovered_color from is_hovered_callback, to keep it.
+    pub fn animate_color(&mut self, color: Color, duration: f32, easing: Easing) {
+        self.animations.color = Some(Tween::new(self.get_color(), color, duration, easing));
     }
 
+    ///Resize from the current sides to sides over duration seconds, rebuilding as they change
+    pub fn animate_sides(&mut self, sides: Vector2, duration: f32, easing: Easing) {
+        self.animations.sides = Some(Tween::new(self.geometry.sides, sides, duration, easing));
+    }
+
+    ///Move from the current margins to margins over duration seconds, rebuilding as they change
+    pub fn animate_margins(&mut self, margins: Directions2D, duration: f32, easing: Easing) {
+        self.animations.margins =
+            Some(Tween::new(self.geometry.margins, margins, duration, easing));
+    }
+
+    ///Fade from the current opacity to opacity over duration seconds
+    pub fn animate_opacity(&mut self, opacity: f32, duration: f32, easing: Easing) {
+        self.animations.opacity = Some(Tween::new(self.opacity, opacity, duration, easing));
+    }
+
+    ///Fill the button is drawn with: the fading color while animate_color runs, otherwise its own color for its
+    ///current state, its own color or the theme's
     pub fn get_color(&self) -> Color {
-        self.color
+        if let Some(color) = self.animated_color {
+            return color;
+        }
+        let (own, themed) = match self.get_state() {
+            ButtonState::Normal => (None, self.style.color),
+            ButtonState::Hovered => (self.hovered_color, self.style.hovered),
+            ButtonState::Pressed => (self.pressed_color, self.style.pressed),
+            ButtonState::Disabled => (self.disabled_color, self.style.disabled),
+        };
+        own.or(self.color).unwrap_or(themed)
+    }
+
+    ///Color of the texts inside the button in its current state
+    pub fn get_text_color(&self) -> Color {
+        let own = match self.get_state() {
+            ButtonState::Normal => None,
+            ButtonState::Hovered => self.hovered_text_color,
+            ButtonState::Pressed => self.pressed_text_color,
+            ButtonState::Disabled => self.disabled_text_color,
+        };
+        own.or(self.text_color).unwrap_or(self.style.text_color)
+    }
+
+    pub fn get_state(&self) -> ButtonState {
+        if self.is_disabled {
+            ButtonState::Disabled
+        } else if self.is_pressed {
+            ButtonState::Pressed
+        } else if self.is_hovered {
+            ButtonState::Hovered
+        } else {
+            ButtonState::Normal
+        }
+    }
+
+    pub fn get_opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    pub fn get_is_animating(&self) -> bool {
+        self.animations.is_running()
     }
 
     pub fn get_is_disabled(&self) -> bool {
         self.is_disabled
     }
+
+    pub fn get_is_hovered(&self) -> bool {
+        self.is_hovered
+    }
+
+    pub fn get_is_pressed(&self) -> bool {
+        self.is_pressed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Button, ButtonState};
+    use crate::column::Column;
+    use crate::event::Event;
+    use crate::render::{DrawCommand, RecordingRenderer};
+    use crate::text::Text;
+    use crate::{Geometry, Vector2, View};
+    use macroquad::prelude::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    const INSIDE: Vector2 = Vector2 {
+        x: 400f32,
+        y: 300f32,
+    };
+    const OUTSIDE: Vector2 = Vector2 {
+        x: 400f32,
+        y: 500f32,
+    };
+
+    fn pointer(view: &mut View, event: fn(Vector2) -> Event, position: Vector2) {
+        view.handle_event(&event(position));
+    }
+
+    fn down(position: Vector2) -> Event {
+        Event::PointerDown {
+            position,
+            button: MouseButton::Left,
+        }
+    }
+
+    fn up(position: Vector2) -> Event {
+        Event::PointerUp {
+            position,
+            button: MouseButton::Left,
+        }
+    }
+
+    fn moved(position: Vector2) -> Event {
+        Event::PointerMove { position }
+    }
+
+    fn button(view: &View) -> &Button<u32> {
+        view.find_by_key("play")
+            .and_then(|widget| widget.downcast_ref::<Button<u32>>())
+            .unwrap()
+    }
+
+    fn label_color(view: &View) -> Option<Color> {
+        let mut renderer = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        view.draw_with(&mut renderer);
+        renderer
+            .commands
+            .into_iter()
+            .find_map(|command| match command {
+                DrawCommand::Text { color, .. } => Some(color),
+                _ => None,
+            })
+    }
+
+    #[test]
+    fn presses_fire_on_release_inside() {
+        let renderer = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        let presses = Rc::new(RefCell::new(0u32));
+        let mut view = View::new(
+            Column::new().push(
+                Button::default(presses.clone())
+                    .geometry(Geometry::new(Vector2::from(100, 50)))
+                    .key("play")
+                    .hovered_color(BEIGE)
+                    .pressed_color(RED)
+                    .pressed_text_color(BLACK)
+                    .is_pressed_callback(|button: &mut Button<u32>| *button.state.borrow_mut() += 1)
+                    .child(Text::default().text("play")),
+            ),
+        );
+        view.build_with(&renderer);
+        assert_eq!(button(&view).get_color(), WHITE);
+        pointer(&mut view, moved, INSIDE);
+        assert_eq!(button(&view).get_color(), BEIGE);
+
+        pointer(&mut view, down, INSIDE);
+        assert_eq!(button(&view).get_state(), ButtonState::Pressed);
+        assert_eq!(button(&view).get_color(), RED);
+        assert_eq!(*presses.borrow(), 0);
+        //the label takes the pressed text color once rebuilt
+        assert!(view.root().get_build());
+        view.build_with(&renderer);
+        assert_eq!(label_color(&view), Some(BLACK));
+        pointer(&mut view, up, INSIDE);
+        assert_eq!(*presses.borrow(), 1);
+        assert_eq!(button(&view).get_state(), ButtonState::Hovered);
+        view.build_with(&renderer);
+        assert_eq!(label_color(&view), Some(WHITE));
+
+        //dragging off cancels the press, even if the pointer comes back before it is released
+        pointer(&mut view, down, INSIDE);
+        pointer(&mut view, moved, OUTSIDE);
+        assert_eq!(button(&view).get_state(), ButtonState::Normal);
+        pointer(&mut view, moved, INSIDE);
+        pointer(&mut view, up, INSIDE);
+        assert_eq!(*presses.borrow(), 1);
+        //releasing over the button after pressing elsewhere does nothing either
+        pointer(&mut view, down, OUTSIDE);
+        pointer(&mut view, up, INSIDE);
+        assert_eq!(*presses.borrow(), 1);
+
+        view.find_by_key_mut("play")
+            .and_then(|widget| widget.downcast_mut::<Button<u32>>())
+            .unwrap()
+            .set_is_disabled(true);
+        assert_eq!(button(&view).get_color(), GRAY);
+        pointer(&mut view, down, INSIDE);
+        pointer(&mut view, up, INSIDE);
+        assert_eq!(*presses.borrow(), 1);
+    }
 }
@@ -3,13 +3,23 @@ This file is a copy of row.rs, with appopriate changes made for redering vertica
 to this difference must be made in row.rs as well.
 */
 
+use super::event::Event;
+use super::focus::Direction;
+use super::key::Key;
+use super::layout::{BuildContext, LayoutError, LayoutPolicy};
+use super::render::Renderer;
 use super::{Directions2D, Geometry, Vector2, Widget};
 use macroquad::prelude::*;
+use std::any::Any;
 
 pub struct Column {
     children: Vec<Box<dyn Widget>>,
     geometry: Geometry,
     id: u16,
+    ///identifies the widget within its View, see Widget::get_key
+    key: Option<Key>,
+    ///number of children, from the first, that fit in the last build. Only these are drawn.
+    laid_out: usize,
 }
 
 impl Column {
@@ -17,6 +27,8 @@ impl Column {
         Column {
             children: Vec::new(),
             id: 0,
+            key: None,
+            laid_out: 0,
             geometry: Geometry::new(Vector2 {
                 x: 100f32,
                 y: 100f32,
@@ -41,9 +53,17 @@ impl Column {
         Self { id, ..self }
     }
 
+    ///Key identifying the widget within its View. Must be unique.
+    pub fn key<K: Into<Key>>(self, key: K) -> Self {
+        Self {
+            key: Some(key.into()),
+            ..self
+        }
+    }
+
     #[cfg(feature = "debug_draw")]
-    fn debug_draw(&self) {
-        draw_rectangle_lines(
+    fn debug_draw(&self, renderer: &mut dyn Renderer) {
+        renderer.draw_rectangle_lines(
             self.geometry.top_left.x,
             self.geometry.top_left.y,
             self.geometry.abs_sides.x,
@@ -51,7 +71,7 @@ impl Column {
             1.0,
             MAGENTA,
         );
-        draw_circle(
+        renderer.draw_circle(
             self.geometry.top_left.x,
             self.geometry.top_left.y,
             20f32,
@@ -60,6 +80,12 @@ impl Column {
     }
 }
 
+impl Default for Column {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Widget for Column {
     fn get_build(&self) -> bool {
         for child in &self.children {
@@ -74,30 +100,90 @@ impl Widget for Column {
         self.id
     }
 
-    fn draw(&self) {
+    fn get_key(&self) -> Option<&Key> {
+        self.key.as_ref()
+    }
+
+    fn draw(&self, renderer: &mut dyn Renderer) {
         #[cfg(feature = "debug_draw")]
-        self.debug_draw();
-        for child in self.children.iter() {
-            child.draw();
+        self.debug_draw(renderer);
+        for child in self.children.iter().take(self.laid_out) {
+            child.draw(renderer);
         }
     }
 
     fn tick(&mut self) {
-        for child in self.children.iter_mut() {
+        for child in self.children.iter_mut().take(self.laid_out) {
             child.tick();
         }
     }
 
+    fn handle_event(&mut self, event: &Event) -> bool {
+        if let Some(position) = event.position() {
+            if event.is_targeted() && !self.geometry.contains(position) {
+                return false;
+            }
+        }
+        //later children are drawn over earlier ones, so they get the event first
+        for child in self.children.iter_mut().take(self.laid_out).rev() {
+            if child.handle_event(event) {
+                return true;
+            }
+        }
+        false
+    }
+
     fn get_side(&self) -> Vector2 {
         self.geometry.sides
     }
 
-    fn build(&mut self, geometry: &Geometry, margins: Option<Directions2D>) -> Vector2 {
+    fn get_geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+
+    fn kind(&self) -> &'static str {
+        "column"
+    }
+
+    fn child_count(&self) -> usize {
+        self.children.len()
+    }
+
+    fn child(&self, index: usize) -> Option<&dyn Widget> {
+        self.children.get(index).map(|child| child.as_ref())
+    }
+
+    fn child_mut(&mut self, index: usize) -> Option<&mut dyn Widget> {
+        match self.children.get_mut(index) {
+            Some(child) => Some(child.as_mut()),
+            None => None,
+        }
+    }
+
+    fn is_child_laid_out(&self, index: usize) -> bool {
+        index < self.laid_out
+    }
+
+    fn navigate(&self, from: usize, direction: Direction) -> Option<usize> {
+        match direction {
+            Direction::Up => from.checked_sub(1),
+            Direction::Down if from + 1 < self.laid_out => Some(from + 1),
+            _ => None,
+        }
+    }
+
+    fn build(
+        &mut self,
+        geometry: &Geometry,
+        margins: Option<Directions2D>,
+        ctx: &mut BuildContext,
+    ) -> Result<Vector2, LayoutError> {
+        let sides = self.geometry.validate(self.id, ctx)?;
         //Find original dimensions of parent and
         //calculate dimensions of this widget from parent dimensions and positions from margins
         let dimensions = Vector2 {
-            x: geometry.abs_sides.x * self.geometry.sides.x / 100f32,
-            y: geometry.abs_sides.y * self.geometry.sides.y / 100f32,
+            x: geometry.abs_sides.x * sides.x / 100f32,
+            y: geometry.abs_sides.y * sides.y / 100f32,
         };
         self.geometry.abs_sides = dimensions;
         let margins = margins.unwrap_or(Directions2D {
@@ -117,14 +203,38 @@ impl Widget for Column {
         //calculate spacing for children and verify that there is no overflow
 
         //records space currently occupied by widget's children, used to calculate margins
-        let mut occupied_space: f32 = 0f32;
-        let count: i32 = self.children.len() as i32 + 1;
-        for child in self.children.iter() {
-            occupied_space += child.get_side().y;
-        }
+        let mut occupied_space: f32 = self
+            .children
+            .iter()
+            .map(|child| child.get_side().y.max(0f32))
+            .sum();
+        self.laid_out = self.children.len();
+        //space the children are sized against
+        let mut parent = self.geometry;
         if occupied_space > 100f32 {
-            panic!("Overflow! widgets exceeded 100!");
+            ctx.report(LayoutError::Overflow {
+                id: self.id,
+                total: occupied_space,
+            })?;
+            if ctx.policy == LayoutPolicy::Clamp {
+                //shrink the space children are sized against so that they fit exactly
+                parent.abs_sides.y *= 100f32 / occupied_space;
+                occupied_space = 100f32;
+            } else {
+                //leave out every child from the first one that doesn't fit
+                occupied_space = 0f32;
+                self.laid_out = 0;
+                for child in self.children.iter() {
+                    let side = child.get_side().y.max(0f32);
+                    if occupied_space + side > 100f32 {
+                        break;
+                    }
+                    occupied_space += side;
+                    self.laid_out += 1;
+                }
+            }
         }
+        let count: i32 = self.laid_out as i32 + 1;
         let mut child_margins = Directions2D::new(
             (100f32 - occupied_space) * dimensions.y / 100f32,
             (100f32 - occupied_space) * dimensions.y / 100f32,
@@ -136,41 +246,107 @@ impl Widget for Column {
         child_margins.top /= count as f32;
         child_margins.bottom /= count as f32;
 
-        let mut it = self.children.iter_mut();
+        let mut it = self.children.iter_mut().take(self.laid_out);
 
         if let Some(child) = it.next() {
             let h_margin = (100f32 - child.get_side().x) * dimensions.x / 200f32;
 
-            self.geometry.top_left_curr.y = child
+            parent.top_left_curr.y = child
                 .build(
-                    &self.geometry,
+                    &parent,
                     Some(Directions2D {
                         left: h_margin,
                         right: h_margin,
                         ..child_margins
                     }),
-                )
+                    ctx,
+                )?
                 .y;
         }
-        while let Some(child) = it.next() {
+        for child in it {
             let h_margin = (100f32 - child.get_side().x) * dimensions.x / 200f32;
 
-            self.geometry.top_left_curr.y = child
+            parent.top_left_curr.y = child
                 .build(
-                    &self.geometry,
+                    &parent,
                     Some(Directions2D {
                         top: 0f32,
                         left: h_margin,
                         right: h_margin,
                         ..child_margins
                     }),
-                )
+                    ctx,
+                )?
                 .y;
         }
+        self.geometry.top_left_curr = parent.top_left_curr;
         //return TL offset
-        Vector2 {
+        Ok(Vector2 {
             x: geometry.top_left_curr.x + margins.left + dimensions.x + margins.right,
             y: geometry.top_left_curr.y + margins.top + dimensions.y + margins.bottom,
-        }
+        })
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::button::Button;
+    use crate::snapshot::snapshot;
+    use crate::View;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn spaces_children_evenly() {
+        let state = Rc::new(RefCell::new(()));
+        let mut view = View::new(
+            Column::new()
+                .push(
+                    Button::default(state.clone())
+                        .id(1)
+                        .geometry(Geometry::new(Vector2::from(40, 40))),
+                )
+                .push(
+                    Button::default(state)
+                        .id(2)
+                        .geometry(Geometry::new(Vector2::from(40, 40))),
+                ),
+        );
+        assert_eq!(
+            snapshot(&mut view, Vector2::new(800f32, 600f32)).unwrap(),
+            "\
+column id=0 top_left=(0.00, 0.00) abs_sides=(800.00, 600.00) abs_margins=(0.00, 0.00, 0.00, 0.00)
+  button id=1 top_left=(240.00, 40.00) abs_sides=(320.00, 240.00) abs_margins=(40.00, 40.00, 240.00, 240.00)
+  button id=2 top_left=(240.00, 320.00) abs_sides=(320.00, 240.00) abs_margins=(0.00, 40.00, 240.00, 240.00)
+"
+        );
+    }
+
+    #[test]
+    fn invalid_geometry_is_reported() {
+        let state = Rc::new(RefCell::new(()));
+        let mut view = View::new(
+            Column::new().push(
+                Button::default(state)
+                    .id(3)
+                    .geometry(Geometry::new(Vector2::from(-10, 40))),
+            ),
+        );
+        assert_eq!(
+            snapshot(&mut view, Vector2::new(800f32, 600f32)),
+            Err(LayoutError::InvalidGeometry {
+                id: 3,
+                sides: Vector2::from(-10, 40)
+            })
+        );
     }
 }
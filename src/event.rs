@@ -0,0 +1,165 @@
+use super::Vector2;
+use macroquad::miniquad::KeyMods;
+use macroquad::prelude::*;
+
+///Keys that are reported as KeyDown and KeyUp events. Typed text is reported separately as Text events.
+const KEYS: &[KeyCode] = &[
+    KeyCode::Tab,
+    KeyCode::Enter,
+    KeyCode::KpEnter,
+    KeyCode::Space,
+    KeyCode::Escape,
+    KeyCode::Backspace,
+    KeyCode::Delete,
+    KeyCode::Insert,
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::A,
+    KeyCode::C,
+    KeyCode::V,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+];
+
+///A single piece of user input, dispatched through the widget tree by View
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    ///A mouse button was pressed. Touches also produce pointer events while macroquad simulates the mouse with touch.
+    PointerDown {
+        position: Vector2,
+        button: MouseButton,
+    },
+    ///A mouse button was released
+    PointerUp {
+        position: Vector2,
+        button: MouseButton,
+    },
+    ///The mouse moved
+    PointerMove {
+        position: Vector2,
+    },
+    ///A finger touched, moved on, or left the screen
+    Touch {
+        id: u64,
+        phase: TouchPhase,
+        position: Vector2,
+    },
+    KeyDown {
+        key: KeyCode,
+        modifiers: KeyMods,
+    },
+    KeyUp {
+        key: KeyCode,
+        modifiers: KeyMods,
+    },
+    ///The mouse wheel was scrolled while the pointer was at position
+    Scroll {
+        delta: Vector2,
+        position: Vector2,
+    },
+    ///A character was typed
+    Text(char),
+}
+
+impl Event {
+    ///Where on the screen the event happened, for events that have a position
+    pub fn position(&self) -> Option<Vector2> {
+        match *self {
+            Event::PointerDown { position, .. }
+            | Event::PointerUp { position, .. }
+            | Event::PointerMove { position }
+            | Event::Touch { position, .. }
+            | Event::Scroll { position, .. } => Some(position),
+            _ => None,
+        }
+    }
+
+    ///Whether the event is meant only for the widgets under it. Containers don't pass these on to children that
+    ///are elsewhere. Other positional events, like the pointer moving or being released, reach every widget so that
+    ///widgets can notice the pointer leaving them.
+    pub fn is_targeted(&self) -> bool {
+        matches!(
+            self,
+            Event::PointerDown { .. }
+                | Event::Scroll { .. }
+                | Event::Touch {
+                    phase: TouchPhase::Started,
+                    ..
+                }
+        )
+    }
+}
+
+///Collects macroquad's input state into events, once per frame
+#[derive(Default)]
+pub struct Input {
+    ///pointer position in the previous frame, used to detect movement
+    pointer: Option<Vector2>,
+}
+
+impl Input {
+    ///Return all events that happened since the previous call, in the order pointer, touch, keyboard, text
+    pub fn collect(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        let (x, y) = mouse_position();
+        let position = Vector2::new(x, y);
+        if self.pointer != Some(position) {
+            events.push(Event::PointerMove { position });
+            self.pointer = Some(position);
+        }
+        for button in [MouseButton::Left, MouseButton::Right, MouseButton::Middle] {
+            if is_mouse_button_pressed(button) {
+                events.push(Event::PointerDown { position, button });
+            }
+            if is_mouse_button_released(button) {
+                events.push(Event::PointerUp { position, button });
+            }
+        }
+        let (wheel_x, wheel_y) = mouse_wheel();
+        if wheel_x != 0f32 || wheel_y != 0f32 {
+            events.push(Event::Scroll {
+                delta: Vector2::new(wheel_x, wheel_y),
+                position,
+            });
+        }
+
+        for touch in touches() {
+            events.push(Event::Touch {
+                id: touch.id,
+                phase: touch.phase,
+                position: Vector2::new(touch.position.x, touch.position.y),
+            });
+        }
+
+        let modifiers = KeyMods {
+            shift: is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift),
+            ctrl: is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl),
+            alt: is_key_down(KeyCode::LeftAlt) || is_key_down(KeyCode::RightAlt),
+            logo: is_key_down(KeyCode::LeftSuper) || is_key_down(KeyCode::RightSuper),
+        };
+        for key in KEYS.iter().copied() {
+            if is_key_pressed(key) {
+                events.push(Event::KeyDown { key, modifiers });
+            }
+            if is_key_released(key) {
+                events.push(Event::KeyUp { key, modifiers });
+            }
+        }
+
+        while let Some(character) = get_char_pressed() {
+            //control characters are already reported as keys
+            if !character.is_control() {
+                events.push(Event::Text(character));
+            }
+        }
+        events
+    }
+}
@@ -0,0 +1,429 @@
+/*
+Keyboard focus. The focused widget is remembered by its path from the root: the index of each child to descend into.
+Tab and Shift-Tab move through focusable widgets in the order they're drawn. Arrow keys move between siblings the way
+their container lays them out (see Widget::navigate), falling back to the container's own siblings when there is
+nothing further in that direction. Clicking a focusable widget focuses it too, but only keyboard and gamepad focus is
+outlined. Widgets are told when they gain or lose focus through Widget::set_focused.
+*/
+
+use super::event::Event;
+use super::render::Renderer;
+use super::{Vector2, Widget};
+use macroquad::prelude::*;
+
+///A direction to move focus in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+///Tracks which widget in a View has keyboard focus
+pub struct FocusManager {
+    ///path of child indices from the root to the focused widget
+    focused: Option<Vec<usize>>,
+    ///color of the outline drawn around the focused widget, None to draw nothing
+    indicator: Option<Color>,
+    ///whether the outline is drawn. Focus moved with the pointer isn't outlined.
+    visible: bool,
+}
+
+impl Default for FocusManager {
+    fn default() -> Self {
+        FocusManager {
+            focused: None,
+            indicator: Some(YELLOW),
+            visible: false,
+        }
+    }
+}
+
+impl FocusManager {
+    ///Path of the focused widget from the root, if any widget is focused
+    pub fn focused(&self) -> Option<&[usize]> {
+        self.focused.as_deref()
+    }
+
+    ///Focus the widget at path under root, or clear focus with None
+    pub fn set_focused(&mut self, root: &mut dyn Widget, path: Option<Vec<usize>>) {
+        self.move_focus(root, path, true);
+    }
+
+    ///Set the color of the outline drawn around the focused widget, None to draw nothing
+    pub fn set_indicator(&mut self, indicator: Option<Color>) {
+        self.indicator = indicator;
+    }
+
+    ///Move focus to the next focusable widget in drawing order, or the previous one if forward is false. Wraps around.
+    pub fn focus_next(&mut self, root: &mut dyn Widget, forward: bool) {
+        let paths = focusable_paths(root);
+        if paths.is_empty() {
+            return self.move_focus(root, None, true);
+        }
+        let current = self
+            .focused
+            .as_ref()
+            .and_then(|focused| paths.iter().position(|path| path == focused));
+        let next = match (current, forward) {
+            (Some(index), true) => (index + 1) % paths.len(),
+            (Some(index), false) => (index + paths.len() - 1) % paths.len(),
+            (None, true) => 0,
+            (None, false) => paths.len() - 1,
+        };
+        self.move_focus(root, Some(paths[next].clone()), true);
+    }
+
+    ///Move focus to the neighbouring focusable widget in direction, following the layout of the containers.
+    ///Focuses the first focusable widget if nothing is focused yet.
+    pub fn focus_direction(&mut self, root: &mut dyn Widget, direction: Direction) {
+        let focused = match self.valid_focus(root) {
+            Some(focused) => focused,
+            None => return self.focus_next(root, true),
+        };
+        //try the focused widget's siblings first, then its ancestors' siblings
+        for depth in (0..focused.len()).rev() {
+            let parent = match widget_at(root, &focused[..depth]) {
+                Some(parent) => parent,
+                None => continue,
+            };
+            let mut index = focused[depth];
+            while let Some(next) = parent.navigate(index, direction) {
+                let mut path = focused[..depth].to_vec();
+                path.push(next);
+                if let Some(found) = parent
+                    .child(next)
+                    .and_then(|child| first_focusable(child, path))
+                {
+                    return self.move_focus(root, Some(found), true);
+                }
+                //skip over siblings with nothing focusable in them
+                index = next;
+            }
+        }
+    }
+
+    ///Move focus to the focusable widget nearest to the focused one in direction, going by where they were laid out
+    ///rather than by the structure of the tree. Focuses the first focusable widget if nothing is focused yet.
+    pub fn focus_nearest(&mut self, root: &mut dyn Widget, direction: Direction) {
+        let focused = match self.valid_focus(root) {
+            Some(focused) => focused,
+            None => return self.focus_next(root, true),
+        };
+        let from = match widget_at(root, &focused) {
+            Some(widget) => center(widget),
+            None => return,
+        };
+        let mut nearest: Option<(f32, Vec<usize>)> = None;
+        for path in focusable_paths(root) {
+            let to = match widget_at(root, &path) {
+                Some(widget) if path != focused => center(widget),
+                _ => continue,
+            };
+            //distance along direction, and how far off to the side the widget is
+            let (along, across) = match direction {
+                Direction::Up => (from.y - to.y, to.x - from.x),
+                Direction::Down => (to.y - from.y, to.x - from.x),
+                Direction::Left => (from.x - to.x, to.y - from.y),
+                Direction::Right => (to.x - from.x, to.y - from.y),
+            };
+            if along <= 0f32 {
+                continue;
+            }
+            //prefer widgets that are in line with the focused one over ones that are closer but off to the side
+            let score = along + 2f32 * across.abs();
+            let better = match &nearest {
+                Some((best, _)) => score < *best,
+                None => true,
+            };
+            if better {
+                nearest = Some((score, path));
+            }
+        }
+        if let Some((_, path)) = nearest {
+            self.move_focus(root, Some(path), true);
+        }
+    }
+
+    ///Focus the topmost focusable widget under position, or clear focus if there is none. Called by View when the
+    ///pointer is pressed.
+    pub fn focus_at(&mut self, root: &mut dyn Widget, position: Vector2) {
+        let path = focusable_paths(root).into_iter().rev().find(|path| {
+            matches!(widget_at(root, path), Some(widget) if widget.get_geometry().contains(position))
+        });
+        self.move_focus(root, path, false);
+    }
+
+    ///Activate the focused widget, as if it was clicked. Returns true if a widget was focused.
+    pub fn activate(&self, root: &mut dyn Widget) -> bool {
+        match self
+            .focused
+            .as_ref()
+            .and_then(|path| widget_at_mut(root, path))
+        {
+            Some(widget) => {
+                widget.activate();
+                true
+            }
+            None => false,
+        }
+    }
+
+    ///Handle keys that move focus or activate the focused widget. Returns true if the event was handled.
+    pub fn handle_event(&mut self, root: &mut dyn Widget, event: &Event) -> bool {
+        let (key, modifiers) = match *event {
+            Event::KeyDown { key, modifiers } => (key, modifiers),
+            _ => return false,
+        };
+        match key {
+            KeyCode::Tab => self.focus_next(root, !modifiers.shift),
+            KeyCode::Up => self.focus_direction(root, Direction::Up),
+            KeyCode::Down => self.focus_direction(root, Direction::Down),
+            KeyCode::Left => self.focus_direction(root, Direction::Left),
+            KeyCode::Right => self.focus_direction(root, Direction::Right),
+            KeyCode::Enter | KeyCode::KpEnter | KeyCode::Space => return self.activate(root),
+            _ => return false,
+        }
+        true
+    }
+
+    ///Draw an outline around the focused widget
+    pub fn draw(&self, root: &dyn Widget, renderer: &mut dyn Renderer) {
+        let color = match self.indicator {
+            Some(color) => color,
+            None => return,
+        };
+        if !self.visible {
+            return;
+        }
+        if let Some(widget) = self.focused.as_ref().and_then(|path| widget_at(root, path)) {
+            let geometry = widget.get_geometry();
+            renderer.draw_rectangle_lines(
+                geometry.top_left.x,
+                geometry.top_left.y,
+                geometry.abs_sides.x,
+                geometry.abs_sides.y,
+                4f32,
+                color,
+            );
+        }
+    }
+
+    ///Focus path, telling the widgets losing and gaining focus. visible is whether the outline is drawn.
+    fn move_focus(&mut self, root: &mut dyn Widget, path: Option<Vec<usize>>, visible: bool) {
+        self.visible = visible;
+        if self.focused == path {
+            return;
+        }
+        if let Some(widget) = self
+            .focused
+            .take()
+            .as_ref()
+            .and_then(|path| widget_at_mut(root, path))
+        {
+            widget.set_focused(false);
+        }
+        if let Some(widget) = path.as_ref().and_then(|path| widget_at_mut(root, path)) {
+            widget.set_focused(true);
+        }
+        self.focused = path;
+    }
+
+    ///The focused path, if it still leads to a focusable widget. The tree may have changed since it was focused.
+    fn valid_focus(&self, root: &dyn Widget) -> Option<Vec<usize>> {
+        self.focused
+            .as_ref()
+            .filter(|path| matches!(widget_at(root, path), Some(widget) if widget.is_focusable()))
+            .cloned()
+    }
+}
+
+///Center of the widget, as laid out by the last build
+fn center(widget: &dyn Widget) -> Vector2 {
+    let geometry = widget.get_geometry();
+    Vector2::new(
+        geometry.top_left.x + geometry.abs_sides.x / 2f32,
+        geometry.top_left.y + geometry.abs_sides.y / 2f32,
+    )
+}
+
+///Find the widget at path from root
+pub fn widget_at<'a>(root: &'a dyn Widget, path: &[usize]) -> Option<&'a dyn Widget> {
+    match path.split_first() {
+        Some((index, rest)) => widget_at(root.child(*index)?, rest),
+        None => Some(root),
+    }
+}
+
+///Find the widget at path from root, mutably
+pub fn widget_at_mut<'a>(root: &'a mut dyn Widget, path: &[usize]) -> Option<&'a mut dyn Widget> {
+    match path.split_first() {
+        Some((index, rest)) => widget_at_mut(root.child_mut(*index)?, rest),
+        None => Some(root),
+    }
+}
+
+///Paths of every focusable widget under root that was laid out, in drawing order
+fn focusable_paths(root: &dyn Widget) -> Vec<Vec<usize>> {
+    fn collect(widget: &dyn Widget, path: &mut Vec<usize>, paths: &mut Vec<Vec<usize>>) {
+        if widget.is_focusable() {
+            paths.push(path.clone());
+        }
+        //children left out of the layout aren't drawn, so they can't be focused
+        for index in (0..widget.child_count()).filter(|index| widget.is_child_laid_out(*index)) {
+            if let Some(child) = widget.child(index) {
+                path.push(index);
+                collect(child, path, paths);
+                path.pop();
+            }
+        }
+    }
+    let mut paths = Vec::new();
+    collect(root, &mut Vec::new(), &mut paths);
+    paths
+}
+
+///Path of the first focusable widget in widget's subtree, given the path of widget itself
+fn first_focusable(widget: &dyn Widget, path: Vec<usize>) -> Option<Vec<usize>> {
+    focusable_paths(widget).into_iter().next().map(|rest| {
+        let mut path = path;
+        path.extend(rest);
+        path
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::button::Button;
+    use crate::column::Column;
+    use crate::layout::LayoutPolicy;
+    use crate::render::{DrawCommand, RecordingRenderer};
+    use crate::row::Row;
+    use crate::text::Text;
+    use crate::{Geometry, View};
+    use macroquad::miniquad::KeyMods;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn key(key: KeyCode) -> Event {
+        Event::KeyDown {
+            key,
+            modifiers: KeyMods::default(),
+        }
+    }
+
+    //a column of two rows, the first with two buttons and a label, the second with one button
+    fn view(pressed: Rc<RefCell<Vec<u16>>>) -> View {
+        let state = Rc::new(RefCell::new(()));
+        let button = |id: u16| {
+            let pressed = pressed.clone();
+            Button::default(state.clone())
+                .id(id)
+                .geometry(Geometry::new(Vector2::from(30, 50)))
+                .is_pressed_callback(move |button: &mut Button<()>| {
+                    pressed.borrow_mut().push(button.id)
+                })
+        };
+        let mut view = View::new(
+            Column::new()
+                .push(
+                    Row::new()
+                        .geometry(Geometry::new(Vector2::from(100, 50)))
+                        .push(button(1))
+                        .push(Text::default().geometry(Geometry::new(Vector2::from(30, 50))))
+                        .push(button(2)),
+                )
+                .push(
+                    Row::new()
+                        .geometry(Geometry::new(Vector2::from(100, 50)))
+                        .push(button(3)),
+                ),
+        );
+        view.build_with(&RecordingRenderer::new(Vector2::new(800f32, 600f32)));
+        view
+    }
+
+    #[test]
+    fn tab_cycles_through_focusable_widgets() {
+        let mut view = view(Rc::new(RefCell::new(Vec::new())));
+        view.handle_event(&key(KeyCode::Tab));
+        assert_eq!(view.focus().focused(), Some(&[0, 0][..]));
+        view.handle_event(&key(KeyCode::Tab));
+        assert_eq!(view.focus().focused(), Some(&[0, 2][..]));
+        view.handle_event(&key(KeyCode::Tab));
+        view.handle_event(&key(KeyCode::Tab));
+        assert_eq!(view.focus().focused(), Some(&[0, 0][..]));
+        view.handle_event(&Event::KeyDown {
+            key: KeyCode::Tab,
+            modifiers: KeyMods {
+                shift: true,
+                ..KeyMods::default()
+            },
+        });
+        assert_eq!(view.focus().focused(), Some(&[1, 0][..]));
+    }
+
+    #[test]
+    fn arrows_follow_rows_and_columns() {
+        let pressed = Rc::new(RefCell::new(Vec::new()));
+        let mut view = view(pressed.clone());
+        view.handle_event(&key(KeyCode::Right));
+        assert_eq!(view.focus().focused(), Some(&[0, 0][..]));
+        //skips over the label
+        view.handle_event(&key(KeyCode::Right));
+        assert_eq!(view.focus().focused(), Some(&[0, 2][..]));
+        view.handle_event(&key(KeyCode::Right));
+        assert_eq!(view.focus().focused(), Some(&[0, 2][..]));
+        view.handle_event(&key(KeyCode::Down));
+        assert_eq!(view.focus().focused(), Some(&[1, 0][..]));
+        view.handle_event(&key(KeyCode::Enter));
+        assert_eq!(*pressed.borrow(), vec![3]);
+    }
+
+    #[test]
+    fn skips_widgets_left_out_of_the_layout() {
+        let state = Rc::new(RefCell::new(()));
+        let button = |id: u16| {
+            Button::default(state.clone())
+                .id(id)
+                .geometry(Geometry::new(Vector2::from(60, 50)))
+        };
+        let mut view =
+            View::new(Row::new().push(button(1)).push(button(2))).layout_policy(LayoutPolicy::Skip);
+        view.build_with(&RecordingRenderer::new(Vector2::new(800f32, 600f32)));
+        view.handle_event(&key(KeyCode::Tab));
+        assert_eq!(view.focus().focused(), Some(&[0][..]));
+        view.handle_event(&key(KeyCode::Tab));
+        assert_eq!(view.focus().focused(), Some(&[0][..]));
+        view.handle_event(&key(KeyCode::Right));
+        assert_eq!(view.focus().focused(), Some(&[0][..]));
+    }
+
+    #[test]
+    fn draws_indicator_around_focused_widget() {
+        let mut view = view(Rc::new(RefCell::new(Vec::new())));
+        view.handle_event(&key(KeyCode::Tab));
+        let mut renderer = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        view.draw_with(&mut renderer);
+        let focused = view
+            .root()
+            .child(0)
+            .unwrap()
+            .child(0)
+            .unwrap()
+            .get_geometry();
+        assert_eq!(
+            renderer.commands.last(),
+            Some(&DrawCommand::RectangleLines {
+                x: focused.top_left.x,
+                y: focused.top_left.y,
+                w: focused.abs_sides.x,
+                h: focused.abs_sides.y,
+                thickness: 4f32,
+                color: YELLOW,
+            })
+        );
+    }
+}
@@ -0,0 +1,109 @@
+/*
+Fonts used by text widgets. Fonts are loaded into the FontRegistry of a View, and widgets refer to them by handle.
+Handles are resolved to macroquad fonts when the widget tree is built, so text is measured and drawn with the same font.
+A text is drawn in a single font: the widget's own font if it has every character of the text, otherwise the first
+fallback font that does, otherwise macroquad's built in font.
+*/
+
+use macroquad::text::{load_ttf_font_from_bytes, Font, FontError};
+
+///Refers to a font loaded into a FontRegistry
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FontHandle(usize);
+
+struct Entry {
+    font: Font,
+    ///parsed copy of the font, to check which characters it has. None if that is unknown, then it is assumed to
+    ///have every character.
+    glyphs: Option<fontdue::Font>,
+}
+
+///Fonts available to the widgets of a View
+#[derive(Default)]
+pub struct FontRegistry {
+    fonts: Vec<Entry>,
+    ///used by widgets that don't set a font
+    default: Option<FontHandle>,
+    ///tried in order when a widget's font lacks some character of its text
+    fallbacks: Vec<FontHandle>,
+}
+
+impl FontRegistry {
+    pub const fn new() -> Self {
+        FontRegistry {
+            fonts: Vec::new(),
+            default: None,
+            fallbacks: Vec::new(),
+        }
+    }
+
+    ///Load a TTF font, for example one included with include_bytes!. Needs the macroquad window to exist.
+    pub fn load(&mut self, bytes: &[u8]) -> Result<FontHandle, FontError> {
+        let glyphs = fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default())?;
+        let font = load_ttf_font_from_bytes(bytes)?;
+        Ok(self.push(Entry {
+            font,
+            glyphs: Some(glyphs),
+        }))
+    }
+
+    ///Add a font that was already loaded into macroquad. It is assumed to have every character, so it is never
+    ///skipped in favour of a fallback.
+    pub fn insert(&mut self, font: Font) -> FontHandle {
+        self.push(Entry { font, glyphs: None })
+    }
+
+    ///Set the font used by widgets that don't set one. Macroquad's built in font is used if None.
+    pub fn set_default(&mut self, default: Option<FontHandle>) {
+        self.default = default;
+    }
+
+    ///Set the fonts to try, in order, when a widget's font doesn't have every character of its text
+    pub fn set_fallbacks(&mut self, fallbacks: Vec<FontHandle>) {
+        self.fallbacks = fallbacks;
+    }
+
+    ///The font to draw text with, given the font the widget asked for. None means macroquad's built in font.
+    pub fn resolve(&self, font: Option<FontHandle>, text: &str) -> Option<Font> {
+        let has_text = |handle: &FontHandle| match self.fonts.get(handle.0) {
+            Some(Entry {
+                glyphs: Some(glyphs),
+                ..
+            }) => text
+                .chars()
+                .filter(|character| !character.is_whitespace())
+                .all(|character| glyphs.lookup_glyph_index(character) != 0),
+            Some(_) => true,
+            None => false,
+        };
+        font.or(self.default)
+            .iter()
+            .chain(self.fallbacks.iter())
+            .find(|handle| has_text(handle))
+            .map(|handle| self.fonts[handle.0].font)
+    }
+
+    fn push(&mut self, entry: Entry) -> FontHandle {
+        self.fonts.push(entry);
+        FontHandle(self.fonts.len() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_widget_font_then_default() {
+        let mut fonts = FontRegistry::new();
+        assert_eq!(fonts.resolve(None, "text"), None);
+        //fonts added without their data are assumed to have every character
+        let font = fonts.insert(Font::default());
+        assert_eq!(fonts.resolve(Some(font), "text"), Some(Font::default()));
+        assert_eq!(fonts.resolve(None, "text"), None);
+        fonts.set_default(Some(font));
+        assert_eq!(fonts.resolve(None, "text"), Some(Font::default()));
+        //handles from another registry resolve to nothing
+        assert_eq!(FontRegistry::new().resolve(Some(font), "text"), None);
+    }
+}
@@ -0,0 +1,258 @@
+/*
+Gamepad navigation. macroquad doesn't read gamepads itself, so a View is handed a GamepadSource that reports the state
+of the pad every tick: a GilrsGamepad reading physical pads with the gilrs library, available with the gamepads
+feature, a SyntheticGamepad set from code, or a source of your own backed by another library. Changes in state are
+turned into focus movement: the D-pad and left stick move focus to the nearest widget in that direction, and the
+south face button activates the focused widget.
+*/
+
+use super::focus::Direction;
+use super::Vector2;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+///The buttons and axes used for navigation
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GamepadState {
+    pub dpad_up: bool,
+    pub dpad_down: bool,
+    pub dpad_left: bool,
+    pub dpad_right: bool,
+    ///position of the left stick, from -1 to 1 on each axis. Positive y points down, like screen coordinates.
+    pub left_stick: Vector2,
+    ///the bottom face button (A on Xbox pads, Cross on PlayStation pads)
+    pub south: bool,
+}
+
+///Reports the current state of a gamepad
+pub trait GamepadSource {
+    fn state(&mut self) -> GamepadState;
+}
+
+///A gamepad whose state is set from code. Clones share the same state, so one clone can be handed to a View while
+///another is used to press buttons, for example in tests.
+#[derive(Clone, Default)]
+pub struct SyntheticGamepad {
+    state: Rc<RefCell<GamepadState>>,
+}
+
+impl SyntheticGamepad {
+    pub fn new() -> Self {
+        SyntheticGamepad::default()
+    }
+
+    pub fn set_state(&self, state: GamepadState) {
+        *self.state.borrow_mut() = state;
+    }
+}
+
+impl GamepadSource for SyntheticGamepad {
+    fn state(&mut self) -> GamepadState {
+        *self.state.borrow()
+    }
+}
+
+///Physical gamepads, read with gilrs. Reports the pad that was used last, or the first one connected until one is
+///used.
+#[cfg(feature = "gamepads")]
+pub struct GilrsGamepad {
+    gilrs: gilrs::Gilrs,
+    ///pad of the latest event
+    active: Option<gilrs::GamepadId>,
+}
+
+#[cfg(feature = "gamepads")]
+impl GilrsGamepad {
+    ///Start reading gamepads. Fails if the platform's gamepad API can't be used.
+    pub fn new() -> Result<Self, Box<gilrs::Error>> {
+        Ok(GilrsGamepad {
+            gilrs: gilrs::Gilrs::new().map_err(Box::new)?,
+            active: None,
+        })
+    }
+}
+
+#[cfg(feature = "gamepads")]
+impl GamepadSource for GilrsGamepad {
+    fn state(&mut self) -> GamepadState {
+        use gilrs::{Axis, Button};
+        //gilrs only updates the state of the pads while its events are read
+        while let Some(event) = self.gilrs.next_event() {
+            self.active = Some(event.id);
+        }
+        let gilrs = &self.gilrs;
+        let pad = match self.active.and_then(|id| gilrs.connected_gamepad(id)) {
+            Some(pad) => pad,
+            None => match gilrs.gamepads().next() {
+                Some((_, pad)) => pad,
+                None => return GamepadState::default(),
+            },
+        };
+        GamepadState {
+            dpad_up: pad.is_pressed(Button::DPadUp),
+            dpad_down: pad.is_pressed(Button::DPadDown),
+            dpad_left: pad.is_pressed(Button::DPadLeft),
+            dpad_right: pad.is_pressed(Button::DPadRight),
+            //gilrs points y up
+            left_stick: Vector2::new(pad.value(Axis::LeftStickX), -pad.value(Axis::LeftStickY)),
+            south: pad.is_pressed(Button::South),
+        }
+    }
+}
+
+///What the user asked for with the gamepad
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GamepadAction {
+    ///Move focus to the nearest widget in direction
+    Move(Direction),
+    ///Activate the focused widget
+    Activate,
+}
+
+///Turns the state reported by a GamepadSource into navigation actions
+pub struct Gamepad {
+    source: Box<dyn GamepadSource>,
+    ///direction held in the previous poll, so that holding a direction moves only once
+    held: Option<Direction>,
+    ///whether the south button was down in the previous poll
+    south: bool,
+    ///how far the stick has to be pushed before it counts as a direction, from 0 to 1
+    deadzone: f32,
+}
+
+impl Gamepad {
+    pub fn new<T: GamepadSource + 'static>(source: T) -> Self {
+        Gamepad {
+            source: Box::new(source),
+            held: None,
+            south: false,
+            deadzone: 0.5,
+        }
+    }
+
+    pub fn deadzone(self, deadzone: f32) -> Self {
+        Gamepad { deadzone, ..self }
+    }
+
+    ///Read the source and return the actions started since the last poll. Directions and the south button trigger
+    ///once when pressed, not again while they're held.
+    pub fn poll(&mut self) -> Vec<GamepadAction> {
+        let state = self.source.state();
+        let mut actions = Vec::new();
+
+        let direction = self.direction(&state);
+        if direction.is_some() && direction != self.held {
+            actions.extend(direction.map(GamepadAction::Move));
+        }
+        self.held = direction;
+
+        if state.south && !self.south {
+            actions.push(GamepadAction::Activate);
+        }
+        self.south = state.south;
+        actions
+    }
+
+    ///Direction held on the D-pad, or failing that on the left stick
+    fn direction(&self, state: &GamepadState) -> Option<Direction> {
+        if state.dpad_up {
+            Some(Direction::Up)
+        } else if state.dpad_down {
+            Some(Direction::Down)
+        } else if state.dpad_left {
+            Some(Direction::Left)
+        } else if state.dpad_right {
+            Some(Direction::Right)
+        } else {
+            let Vector2 { x, y } = state.left_stick;
+            if x.abs().max(y.abs()) < self.deadzone {
+                None
+            } else if x.abs() > y.abs() {
+                Some(if x > 0f32 {
+                    Direction::Right
+                } else {
+                    Direction::Left
+                })
+            } else {
+                Some(if y > 0f32 {
+                    Direction::Down
+                } else {
+                    Direction::Up
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::button::Button;
+    use crate::grid::Grid;
+    use crate::render::RecordingRenderer;
+    use crate::View;
+
+    #[test]
+    fn directions_trigger_once_while_held() {
+        let pad = SyntheticGamepad::new();
+        let mut gamepad = Gamepad::new(pad.clone());
+        pad.set_state(GamepadState {
+            left_stick: Vector2::new(0.2, -0.9),
+            ..GamepadState::default()
+        });
+        assert_eq!(gamepad.poll(), vec![GamepadAction::Move(Direction::Up)]);
+        assert_eq!(gamepad.poll(), vec![]);
+        pad.set_state(GamepadState {
+            left_stick: Vector2::new(0.3, 0.1),
+            south: true,
+            ..GamepadState::default()
+        });
+        assert_eq!(gamepad.poll(), vec![GamepadAction::Activate]);
+        pad.set_state(GamepadState {
+            dpad_right: true,
+            ..GamepadState::default()
+        });
+        assert_eq!(gamepad.poll(), vec![GamepadAction::Move(Direction::Right)]);
+    }
+
+    #[test]
+    fn moves_focus_to_nearest_widget() {
+        let pressed = Rc::new(RefCell::new(Vec::new()));
+        let state = Rc::new(RefCell::new(()));
+        let mut grid = Grid::new(3, 3);
+        for id in 0..9 {
+            let pressed = pressed.clone();
+            grid = grid.push(Button::default(state.clone()).id(id).is_pressed_callback(
+                move |button: &mut Button<()>| pressed.borrow_mut().push(button.id),
+            ));
+        }
+        let pad = SyntheticGamepad::new();
+        let mut view = View::new(grid).gamepad(pad.clone());
+        view.build_with(&RecordingRenderer::new(Vector2::new(900f32, 900f32)));
+
+        let mut press = |state: GamepadState| {
+            pad.set_state(state);
+            view.handle_gamepad();
+            pad.set_state(GamepadState::default());
+            view.handle_gamepad();
+        };
+        press(GamepadState {
+            dpad_down: true,
+            ..GamepadState::default()
+        });
+        press(GamepadState {
+            dpad_down: true,
+            ..GamepadState::default()
+        });
+        press(GamepadState {
+            dpad_right: true,
+            ..GamepadState::default()
+        });
+        press(GamepadState {
+            south: true,
+            ..GamepadState::default()
+        });
+        //focus starts at the first button, moves down a row, then right
+        assert_eq!(*pressed.borrow(), vec![4]);
+    }
+}
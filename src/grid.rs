@@ -0,0 +1,532 @@
+/*
+Lays children out in cells of a fixed number of columns and rows. Margin handling for the grid itself is the same as in row.rs and
+column.rs; the free space left over by the tracks is divided into equal gaps, just like Row and Column divide it between children.
+*/
+
+use super::event::Event;
+use super::focus::Direction;
+use super::key::Key;
+use super::layout::{BuildContext, LayoutError, LayoutPolicy};
+use super::render::Renderer;
+use super::{Directions2D, Geometry, Vector2, Widget};
+use macroquad::prelude::*;
+use std::any::Any;
+
+///A child of the grid along with the cells it occupies
+struct Cell {
+    widget: Box<dyn Widget>,
+    ///column and row of the top left cell, None if the grid had no room for the child
+    position: Option<(usize, usize)>,
+    column_span: usize,
+    row_span: usize,
+    ///whether the child was laid out in the last build. Only these are drawn.
+    laid_out: bool,
+}
+
+pub struct Grid {
+    cells: Vec<Cell>,
+    geometry: Geometry,
+    columns: usize,
+    rows: usize,
+    ///width of each column in % of the grid's width
+    column_sizes: Vec<f32>,
+    ///height of each row in % of the grid's height
+    row_sizes: Vec<f32>,
+    ///marks cells that already have a child, in row major order
+    occupied: Vec<bool>,
+    id: u16,
+    ///identifies the widget within its View, see Widget::get_key
+    key: Option<Key>,
+}
+
+impl Grid {
+    ///Create a grid with the given number of columns and rows. All columns and rows are sized equally. A grid without
+    ///columns or rows is reported as invalid geometry when it is built.
+    pub fn new(columns: usize, rows: usize) -> Self {
+        Grid {
+            cells: Vec::new(),
+            geometry: Geometry::new(Vector2 {
+                x: 100f32,
+                y: 100f32,
+            }),
+            columns,
+            rows,
+            column_sizes: vec![100f32 / columns as f32; columns],
+            row_sizes: vec![100f32 / rows as f32; rows],
+            occupied: vec![false; columns * rows],
+            id: 0,
+            key: None,
+        }
+    }
+
+    ///Set the width of every column in % of the grid's width. Must contain one entry per column, other sizes are
+    ///reported as invalid geometry when the grid is built.
+    pub fn column_sizes(self, column_sizes: Vec<f32>) -> Self {
+        Grid {
+            column_sizes,
+            ..self
+        }
+    }
+
+    ///Set the height of every row in % of the grid's height. Must contain one entry per row, other sizes are
+    ///reported as invalid geometry when the grid is built.
+    pub fn row_sizes(self, row_sizes: Vec<f32>) -> Self {
+        Grid { row_sizes, ..self }
+    }
+
+    ///Add a child occupying a single cell
+    pub fn push<T: Widget + 'static>(self, child: T) -> Self {
+        self.push_span(child, 1, 1)
+    }
+
+    ///Add a child spanning multiple cells. Children are placed in the first free cell, going left to right and top to bottom.
+    ///Children that don't fit anywhere are reported as a layout error when the grid is built.
+    pub fn push_span<T: Widget + 'static>(
+        mut self,
+        child: T,
+        column_span: usize,
+        row_span: usize,
+    ) -> Self {
+        let position = self.find_free(column_span, row_span);
+        if let Some((column, row)) = position {
+            let columns = self.columns;
+            for r in row..row + row_span {
+                for c in column..column + column_span {
+                    self.occupied[r * columns + c] = true;
+                }
+            }
+        }
+        self.cells.push(Cell {
+            widget: Box::new(child),
+            position,
+            column_span,
+            row_span,
+            laid_out: false,
+        });
+        self
+    }
+
+    pub fn geometry(self, geometry: Geometry) -> Self {
+        Grid { geometry, ..self }
+    }
+
+    pub fn id(self, id: u16) -> Self {
+        Self { id, ..self }
+    }
+
+    ///Key identifying the widget within its View. Must be unique.
+    pub fn key<K: Into<Key>>(self, key: K) -> Self {
+        Self {
+            key: Some(key.into()),
+            ..self
+        }
+    }
+
+    ///Find the first cell from which a block of column_span x row_span cells is free
+    fn find_free(&self, column_span: usize, row_span: usize) -> Option<(usize, usize)> {
+        let (columns, rows) = (self.columns, self.rows);
+        if column_span == 0 || row_span == 0 || column_span > columns || row_span > rows {
+            return None;
+        }
+        for row in 0..=rows - row_span {
+            for column in 0..=columns - column_span {
+                let free = (row..row + row_span).all(|r| {
+                    (column..column + column_span).all(|c| !self.occupied[r * columns + c])
+                });
+                if free {
+                    return Some((column, row));
+                }
+            }
+        }
+        None
+    }
+
+    ///Index of the laid out child covering the given cell
+    fn cell_at(&self, column: usize, row: usize) -> Option<usize> {
+        self.cells.iter().position(|cell| match cell.position {
+            Some((c, r)) => {
+                cell.laid_out
+                    && (c..c + cell.column_span).contains(&column)
+                    && (r..r + cell.row_span).contains(&row)
+            }
+            None => false,
+        })
+    }
+
+    ///Sizes of count tracks as set by the user. A grid without tracks, or with a number of sizes that doesn't match
+    ///the number of tracks, is invalid. If the layout policy recovers from that, the tracks are sized equally.
+    fn track_sizes(
+        &self,
+        sizes: &[f32],
+        count: usize,
+        ctx: &mut BuildContext,
+    ) -> Result<Vec<f32>, LayoutError> {
+        if count > 0 && sizes.len() == count {
+            return Ok(sizes.to_vec());
+        }
+        ctx.report(LayoutError::InvalidGeometry {
+            id: self.id,
+            sides: self.geometry.sides,
+        })?;
+        Ok(vec![100f32 / count as f32; count])
+    }
+
+    ///Sizes of the tracks that can be laid out, after verifying that they don't overflow. Tracks that are left out
+    ///by the layout policy are not returned.
+    fn fit_tracks(&self, sizes: &[f32], ctx: &mut BuildContext) -> Result<Vec<f32>, LayoutError> {
+        let occupied_space: f32 = sizes.iter().map(|size| size.max(0f32)).sum();
+        if occupied_space <= 100f32 {
+            return Ok(sizes.iter().map(|size| size.max(0f32)).collect());
+        }
+        ctx.report(LayoutError::Overflow {
+            id: self.id,
+            total: occupied_space,
+        })?;
+        if ctx.policy == LayoutPolicy::Clamp {
+            //shrink every track proportionally so that they fit exactly
+            Ok(sizes
+                .iter()
+                .map(|size| size.max(0f32) * 100f32 / occupied_space)
+                .collect())
+        } else {
+            //leave out every track from the first one that doesn't fit
+            let mut fitting = Vec::new();
+            let mut occupied_space = 0f32;
+            for size in sizes.iter().map(|size| size.max(0f32)) {
+                if occupied_space + size > 100f32 {
+                    break;
+                }
+                occupied_space += size;
+                fitting.push(size);
+            }
+            Ok(fitting)
+        }
+    }
+
+    ///Absolute offsets of the start of each track from the grid's top left, along with the absolute gap between tracks
+    fn track_offsets(sizes: &[f32], length: f32) -> (Vec<f32>, f32) {
+        let occupied_space: f32 = sizes.iter().sum();
+        //tracks are separated by equal gaps, including before the first and after the last track
+        let gap = (100f32 - occupied_space) * length / 100f32 / (sizes.len() + 1) as f32;
+        let mut offsets = Vec::with_capacity(sizes.len());
+        let mut curr = gap;
+        for size in sizes {
+            offsets.push(curr);
+            curr += size * length / 100f32 + gap;
+        }
+        (offsets, gap)
+    }
+
+    #[cfg(feature = "debug_draw")]
+    fn debug_draw(&self, renderer: &mut dyn Renderer) {
+        renderer.draw_rectangle_lines(
+            self.geometry.top_left.x,
+            self.geometry.top_left.y,
+            self.geometry.abs_sides.x,
+            self.geometry.abs_sides.y,
+            1.0,
+            MAGENTA,
+        );
+        renderer.draw_circle(
+            self.geometry.top_left.x,
+            self.geometry.top_left.y,
+            20f32,
+            RED,
+        );
+    }
+}
+
+impl Widget for Grid {
+    fn get_build(&self) -> bool {
+        for cell in &self.cells {
+            if cell.widget.get_build() {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn get_id(&self) -> u16 {
+        self.id
+    }
+
+    fn get_key(&self) -> Option<&Key> {
+        self.key.as_ref()
+    }
+
+    fn draw(&self, renderer: &mut dyn Renderer) {
+        #[cfg(feature = "debug_draw")]
+        self.debug_draw(renderer);
+        for cell in self.cells.iter().filter(|cell| cell.laid_out) {
+            cell.widget.draw(renderer);
+        }
+    }
+
+    fn tick(&mut self) {
+        for cell in self.cells.iter_mut().filter(|cell| cell.laid_out) {
+            cell.widget.tick();
+        }
+    }
+
+    fn handle_event(&mut self, event: &Event) -> bool {
+        if let Some(position) = event.position() {
+            if event.is_targeted() && !self.geometry.contains(position) {
+                return false;
+            }
+        }
+        //later children are drawn over earlier ones, so they get the event first
+        for cell in self.cells.iter_mut().filter(|cell| cell.laid_out).rev() {
+            if cell.widget.handle_event(event) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn get_side(&self) -> Vector2 {
+        self.geometry.sides
+    }
+
+    fn get_geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+
+    fn kind(&self) -> &'static str {
+        "grid"
+    }
+
+    fn child_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn child(&self, index: usize) -> Option<&dyn Widget> {
+        self.cells.get(index).map(|cell| cell.widget.as_ref())
+    }
+
+    fn child_mut(&mut self, index: usize) -> Option<&mut dyn Widget> {
+        match self.cells.get_mut(index) {
+            Some(cell) => Some(cell.widget.as_mut()),
+            None => None,
+        }
+    }
+
+    fn is_child_laid_out(&self, index: usize) -> bool {
+        matches!(self.cells.get(index), Some(cell) if cell.laid_out)
+    }
+
+    fn navigate(&self, from: usize, direction: Direction) -> Option<usize> {
+        let cell = self.cells.get(from)?;
+        let (column, row) = cell.position?;
+        //the cell next to the edge of this one in direction
+        let (column, row) = match direction {
+            Direction::Left => (column.checked_sub(1)?, row),
+            Direction::Right => (column + cell.column_span, row),
+            Direction::Up => (column, row.checked_sub(1)?),
+            Direction::Down => (column, row + cell.row_span),
+        };
+        self.cell_at(column, row)
+    }
+
+    fn build(
+        &mut self,
+        geometry: &Geometry,
+        margins: Option<Directions2D>,
+        ctx: &mut BuildContext,
+    ) -> Result<Vector2, LayoutError> {
+        let sides = self.geometry.validate(self.id, ctx)?;
+        //Find original dimensions of parent and
+        //calculate dimensions of this widget from parent dimensions and positions from margins
+        let dimensions = Vector2 {
+            x: geometry.abs_sides.x * sides.x / 100f32,
+            y: geometry.abs_sides.y * sides.y / 100f32,
+        };
+        self.geometry.abs_sides = dimensions;
+        let margins = margins.unwrap_or(Directions2D {
+            top: geometry.abs_sides.y * self.geometry.margins.top / 100f32,
+            bottom: geometry.abs_sides.y * self.geometry.margins.bottom / 100f32,
+            left: geometry.abs_sides.x * self.geometry.margins.left / 100f32,
+            right: geometry.abs_sides.x * self.geometry.margins.right / 100f32,
+        });
+        self.geometry.abs_margins = margins;
+        let tl = Vector2 {
+            x: geometry.top_left_curr.x + margins.left,
+            y: geometry.top_left_curr.y + margins.top,
+        };
+        self.geometry.top_left = tl;
+        self.geometry.top_left_curr = self.geometry.top_left;
+
+        //calculate where each column and row starts, and verify that there is no overflow
+        let column_sizes = self.track_sizes(&self.column_sizes, self.columns, ctx)?;
+        let row_sizes = self.track_sizes(&self.row_sizes, self.rows, ctx)?;
+        let column_sizes = self.fit_tracks(&column_sizes, ctx)?;
+        let row_sizes = self.fit_tracks(&row_sizes, ctx)?;
+        let (column_offsets, column_gap) = Grid::track_offsets(&column_sizes, dimensions.x);
+        let (row_offsets, row_gap) = Grid::track_offsets(&row_sizes, dimensions.y);
+
+        let unplaced = self
+            .cells
+            .iter()
+            .filter(|cell| cell.position.is_none())
+            .count();
+        if unplaced > 0 {
+            ctx.report(LayoutError::NoRoom {
+                id: self.id,
+                unplaced,
+            })?;
+        }
+
+        for cell in self.cells.iter_mut() {
+            //leave out children without a cell, or whose cells were left out by the layout policy
+            let (column, row) = match cell.position {
+                Some((column, row))
+                    if column + cell.column_span <= column_sizes.len()
+                        && row + cell.row_span <= row_sizes.len() =>
+                {
+                    (column, row)
+                }
+                _ => {
+                    cell.laid_out = false;
+                    continue;
+                }
+            };
+            cell.laid_out = true;
+
+            //a spanning cell also covers the gaps between the tracks it spans
+            let width = column_sizes[column..column + cell.column_span]
+                .iter()
+                .sum::<f32>()
+                * dimensions.x
+                / 100f32
+                + column_gap * (cell.column_span - 1) as f32;
+            let height = row_sizes[row..row + cell.row_span].iter().sum::<f32>() * dimensions.y
+                / 100f32
+                + row_gap * (cell.row_span - 1) as f32;
+
+            //children are built against the cell as if it were their parent, so that their percentages
+            //and default margins are relative to the cell
+            let mut cell_geometry = Geometry::new(Vector2::new(100f32, 100f32));
+            cell_geometry.abs_sides = Vector2::new(width, height);
+            cell_geometry.top_left =
+                Vector2::new(tl.x + column_offsets[column], tl.y + row_offsets[row]);
+            cell_geometry.top_left_curr = cell_geometry.top_left;
+
+            cell.widget.build(&cell_geometry, None, ctx)?;
+        }
+        //return TL offsetted
+        Ok(Vector2 {
+            x: geometry.top_left_curr.x + margins.left + dimensions.x + margins.right,
+            y: geometry.top_left_curr.y + margins.top + dimensions.y + margins.bottom,
+        })
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::button::Button;
+    use crate::snapshot::snapshot;
+    use crate::text::Text;
+    use crate::View;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn button() -> Button<()> {
+        Button::default(Rc::new(RefCell::new(())))
+    }
+
+    #[test]
+    fn fills_free_cells_in_order() {
+        let grid = Grid::new(2, 2)
+            .push_span(button(), 2, 1)
+            .push(button())
+            .push(button());
+        let positions: Vec<_> = grid.cells.iter().map(|cell| cell.position).collect();
+        assert_eq!(positions, vec![Some((0, 0)), Some((0, 1)), Some((1, 1))]);
+    }
+
+    #[test]
+    fn divides_free_space_into_equal_gaps() {
+        let (offsets, gap) = Grid::track_offsets(&[20f32, 20f32, 20f32], 100f32);
+        assert_eq!(offsets, vec![10f32, 40f32, 70f32]);
+        assert_eq!(gap, 10f32);
+    }
+
+    #[test]
+    fn leaves_children_without_room_unplaced() {
+        let grid = Grid::new(1, 1).push(button()).push(button());
+        let positions: Vec<_> = grid.cells.iter().map(|cell| cell.position).collect();
+        assert_eq!(positions, vec![Some((0, 0)), None]);
+    }
+
+    #[test]
+    fn places_spanning_children_in_free_cells() {
+        let state = Rc::new(RefCell::new(()));
+        let mut view = View::new(
+            Grid::new(2, 2)
+                .push_span(Button::default(state.clone()).id(1), 2, 1)
+                .push(Text::default().text("ab").id(2))
+                .push(
+                    Button::default(state)
+                        .id(3)
+                        .geometry(Geometry::new(Vector2::from(50, 50))),
+                ),
+        );
+        assert_eq!(
+            snapshot(&mut view, Vector2::new(800f32, 600f32)).unwrap(),
+            "\
+grid id=0 top_left=(0.00, 0.00) abs_sides=(800.00, 600.00) abs_margins=(0.00, 0.00, 0.00, 0.00)
+  button id=1 top_left=(0.00, 0.00) abs_sides=(800.00, 300.00) abs_margins=(0.00, 0.00, 0.00, 0.00)
+  text id=2 top_left=(0.00, 300.00) abs_sides=(400.00, 300.00) abs_margins=(0.00, 0.00, 0.00, 0.00)
+  button id=3 top_left=(500.00, 375.00) abs_sides=(200.00, 150.00) abs_margins=(75.00, 75.00, 100.00, 100.00)
+"
+        );
+    }
+
+    #[test]
+    fn reports_children_without_room() {
+        let mut view = View::new(
+            Grid::new(1, 1)
+                .id(4)
+                .push(Text::default())
+                .push(Text::default()),
+        );
+        assert_eq!(
+            snapshot(&mut view, Vector2::new(800f32, 600f32)),
+            Err(LayoutError::NoRoom { id: 4, unplaced: 1 })
+        );
+    }
+
+    #[test]
+    fn reports_missing_and_mismatched_tracks() {
+        let invalid = LayoutError::InvalidGeometry {
+            id: 5,
+            sides: Vector2::from(100, 100),
+        };
+        let screen = Vector2::new(800f32, 600f32);
+        let mut view = View::new(Grid::new(0, 2).id(5).push(Text::default()));
+        assert_eq!(snapshot(&mut view, screen), Err(invalid.clone()));
+        let mut view = View::new(Grid::new(2, 1).id(5).column_sizes(vec![50f32]));
+        assert_eq!(snapshot(&mut view, screen), Err(invalid.clone()));
+
+        //recovering sizes the tracks equally
+        let mut view = View::new(
+            Grid::new(2, 1)
+                .id(5)
+                .row_sizes(vec![50f32, 50f32])
+                .push(Text::default())
+                .push(Text::default().id(6)),
+        )
+        .layout_policy(LayoutPolicy::Clamp);
+        let laid_out = snapshot(&mut view, screen).unwrap();
+        assert!(laid_out.contains("text id=6 top_left=(400.00, 0.00) abs_sides=(400.00, 600.00)"));
+        assert_eq!(view.layout_errors(), &[invalid]);
+    }
+}
@@ -0,0 +1,230 @@
+/*
+Hot reloading of layout files, for development. A View made with View::from_file checks the modification time of its
+layout file every tick, and swaps in a new widget tree when the file changes. Widgets in the new tree take over the
+state of the widgets they replace, matched by key, or by path for widgets without one, see Widget::restore_state. A
+file that can't be parsed or laid out doesn't crash the game: the last tree that worked stays up, with the error drawn
+over it until the file is fixed.
+
+Only available with the hot_reload feature, which enables layout_files.
+*/
+
+use super::focus::{widget_at, widget_at_mut};
+use super::layout::LayoutPolicy;
+use super::layout_file::{CallbackRegistry, LayoutFileError, ViewDescription};
+use super::render::Renderer;
+use super::tree;
+use super::Widget;
+use macroquad::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::SystemTime;
+
+type Instantiate = Box<dyn Fn(&ViewDescription) -> Result<Box<dyn Widget>, LayoutFileError>>;
+
+///A layout file watched for changes
+pub struct HotReload {
+    path: PathBuf,
+    ///modification time of the file when it was last loaded, None if it couldn't be read
+    modified: Option<SystemTime>,
+    ///whether the file was loaded at all yet
+    polled: bool,
+    ///turns descriptions into widget trees, with the state and callbacks the View was made with
+    instantiate: Instantiate,
+    ///why the file couldn't be loaded the last time it changed
+    error: Option<String>,
+}
+
+impl HotReload {
+    pub fn new<T: 'static, P: AsRef<Path>>(
+        path: P,
+        state: Rc<RefCell<T>>,
+        callbacks: CallbackRegistry<T>,
+    ) -> Self {
+        HotReload {
+            path: path.as_ref().to_path_buf(),
+            modified: None,
+            polled: false,
+            instantiate: Box::new(move |description| {
+                description.root.instantiate(&state, &callbacks)
+            }),
+            error: None,
+        }
+    }
+
+    ///Load the file if it changed since it was last loaded. Returns the new tree and its layout policy, or None if
+    ///the file is unchanged or couldn't be loaded, see HotReload::error.
+    pub fn poll(&mut self) -> Option<(Box<dyn Widget>, LayoutPolicy)> {
+        let modified = std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        //a missing file is only reported once, until it appears
+        if self.polled && modified == self.modified {
+            return None;
+        }
+        self.polled = true;
+        self.modified = modified;
+        let loaded = ViewDescription::load(&self.path).and_then(|description| {
+            let root = (self.instantiate)(&description)?;
+            Ok((root, description.layout_policy))
+        });
+        match loaded {
+            Ok(loaded) => {
+                self.error = None;
+                Some(loaded)
+            }
+            Err(error) => {
+                self.set_error(error.to_string());
+                None
+            }
+        }
+    }
+
+    ///Why the file couldn't be loaded or laid out the last time it changed
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        warn!("{}: {}", self.path.display(), error);
+        self.error = Some(error);
+    }
+
+    ///Draw the error over the top of the screen, if there is one
+    pub fn draw_error(&self, renderer: &mut dyn Renderer) {
+        let error = match &self.error {
+            Some(error) => error,
+            None => return,
+        };
+        let font_size = 20u16;
+        let lines: Vec<String> = std::iter::once(self.path.display().to_string())
+            .chain(error.lines().map(String::from))
+            .collect();
+        let width = renderer.screen_size().x;
+        let line_height = font_size as f32 * 1.2f32;
+        renderer.draw_rectangle(
+            0f32,
+            0f32,
+            width,
+            line_height * (lines.len() as f32 + 0.5f32),
+            Color::new(0f32, 0f32, 0f32, 0.8f32),
+        );
+        for (index, line) in lines.iter().enumerate() {
+            renderer.draw_text(
+                line.as_str(),
+                line_height / 2f32,
+                line_height * (index as f32 + 1f32),
+                TextParams {
+                    font_size,
+                    color: RED,
+                    ..TextParams::default()
+                },
+            );
+        }
+    }
+}
+
+///Hand every widget in new the state of the widget of the same kind with the same key in old, or at the same path
+///for widgets without a key
+pub fn restore_state(old: &dyn Widget, new: &mut dyn Widget) {
+    let old_paths: HashMap<_, _> = tree::keys(old)
+        .into_iter()
+        .map(|(path, key)| (key, path))
+        .collect();
+    for (path, key) in tree::keys(new) {
+        let previous = match old_paths.get(&key).and_then(|path| widget_at(old, path)) {
+            Some(previous) => previous,
+            None => continue,
+        };
+        if let Some(widget) = widget_at_mut(new, &path) {
+            if widget.kind() == previous.kind() {
+                widget.restore_state(previous);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::button::Button;
+    use crate::event::Event;
+    use crate::layout_file::CallbackRegistry;
+    use crate::render::{DrawCommand, RecordingRenderer};
+    use crate::{Vector2, View};
+    use std::cell::RefCell;
+    use std::fs::File;
+    use std::io::Write;
+    use std::path::Path;
+    use std::rc::Rc;
+    use std::time::{Duration, SystemTime};
+
+    //write source to path, with a modification time that is different every time
+    fn write(path: &Path, source: &str, version: u64) {
+        let mut file = File::create(path).unwrap();
+        file.write_all(source.as_bytes()).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(version))
+            .unwrap();
+    }
+
+    fn button_color(view: &View) -> String {
+        let button = view
+            .find_by_key("start")
+            .unwrap()
+            .downcast_ref::<Button<()>>();
+        format!("{:?}", button.unwrap().get_color())
+    }
+
+    #[test]
+    fn swaps_tree_and_keeps_state_when_file_changes() {
+        let path =
+            std::env::temp_dir().join(format!("mcgooey-hot-reload-{}.ron", std::process::id()));
+        let renderer = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        let menu = |color: &str, height: u32| {
+            format!(
+                r#"(root: Column(children: [Button(key: "start", sides: (50, {}), color: "{}")]))"#,
+                height, color
+            )
+        };
+        write(&path, menu("#ffffff", 50).as_str(), 1);
+        let mut view = View::from_file(&path, Rc::new(RefCell::new(())), CallbackRegistry::new());
+        view.check_reload_with(&renderer);
+        let white = button_color(&view);
+        view.handle_event(&Event::PointerMove {
+            position: Vector2::new(400f32, 300f32),
+        });
+
+        write(&path, menu("#ff0000", 50).as_str(), 2);
+        view.check_reload_with(&renderer);
+        assert_ne!(button_color(&view), white);
+        let button = view
+            .find_by_key("start")
+            .unwrap()
+            .downcast_ref::<Button<()>>();
+        assert!(button.unwrap().get_is_hovered());
+
+        //broken files leave the last tree up and show the error instead
+        write(&path, "(root: Column(", 3);
+        view.check_reload_with(&renderer);
+        assert!(view
+            .reload_error()
+            .unwrap()
+            .starts_with("Invalid layout file"));
+        let mut drawn = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        view.draw_with(&mut drawn);
+        assert!(drawn
+            .commands
+            .iter()
+            .any(|command| matches!(command, DrawCommand::Text { text, .. } if text.starts_with("Invalid"))));
+        write(&path, menu("#00ff00", 150).as_str(), 4);
+        view.check_reload_with(&renderer);
+        assert!(view.reload_error().unwrap().starts_with("Overflow"));
+        assert_ne!(button_color(&view), white);
+
+        write(&path, menu("#ffffff", 50).as_str(), 5);
+        view.check_reload_with(&renderer);
+        assert_eq!(view.reload_error(), None);
+        assert_eq!(button_color(&view), white);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
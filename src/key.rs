@@ -0,0 +1,56 @@
+/*
+Keys identify widgets across rebuilds of a tree. Unlike ids, they don't have to be numbers picked by hand: a key is a
+name, or a value of one of the app's own types, like a variant of an enum listing the widgets it looks up. Keys must be
+unique within a View, which is checked every time it is built. Widgets without a key are identified by their path from
+the root instead, see tree::keys.
+*/
+
+use std::any::type_name;
+use std::fmt;
+
+///Identifies a widget within a View
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Key {
+    ///A name chosen by the user
+    Name(String),
+    ///A value of a user type, kept apart from equal looking values of other types
+    Typed {
+        type_name: &'static str,
+        value: String,
+    },
+    ///Assigned to a widget without a key from where it is in the tree, like column/2/row/1 for the second child of the
+    ///row that is the third child of the root column. The root's path is empty.
+    Path(String),
+}
+
+impl Key {
+    ///Key made from a value of any type that can be debug printed, usually a fieldless enum
+    pub fn typed<K: fmt::Debug + 'static>(value: K) -> Self {
+        Key::Typed {
+            type_name: type_name::<K>(),
+            value: format!("{:?}", value),
+        }
+    }
+}
+
+impl From<&str> for Key {
+    fn from(name: &str) -> Self {
+        Key::Name(String::from(name))
+    }
+}
+
+impl From<String> for Key {
+    fn from(name: String) -> Self {
+        Key::Name(name)
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Key::Name(name) => write!(f, "\"{}\"", name),
+            Key::Typed { type_name, value } => write!(f, "{}::{}", type_name, value),
+            Key::Path(path) => write!(f, "/{}", path),
+        }
+    }
+}
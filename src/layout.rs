@@ -0,0 +1,163 @@
+use super::font::FontRegistry;
+use super::key::Key;
+use super::render::Renderer;
+use super::theme::Theme;
+use super::Vector2;
+use macroquad::logging::warn;
+use std::collections::HashMap;
+use std::fmt;
+
+///Describes why a widget tree could not be laid out as specified
+#[derive(Clone, Debug, PartialEq)]
+pub enum LayoutError {
+    ///The children of a widget need more than 100% of the space along the direction it lays them out in
+    Overflow {
+        ///id of the widget whose children overflow
+        id: u16,
+        ///total space in % requested by the children
+        total: f32,
+    },
+    ///A widget has sides that cannot be laid out, such as negative lengths, or a grid has no columns or rows, or a
+    ///number of track sizes that differs from its number of columns or rows
+    InvalidGeometry {
+        ///id of the offending widget
+        id: u16,
+        ///the sides requested by the widget, in %
+        sides: Vector2,
+    },
+    ///A grid has more children than it has free cells for
+    NoRoom {
+        ///id of the grid
+        id: u16,
+        ///number of children that could not be placed
+        unplaced: usize,
+    },
+    ///Two widgets in the same View have the same key
+    DuplicateKey {
+        key: Key,
+        ///paths from the root to the first two widgets with the key
+        first: Vec<usize>,
+        second: Vec<usize>,
+    },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutError::Overflow { id, total } => write!(
+                f,
+                "Overflow! widgets exceeded 100! Children of widget {} need {}%",
+                id, total
+            ),
+            LayoutError::InvalidGeometry { id, sides } => write!(
+                f,
+                "Widget {} has invalid geometry ({}%, {}%)",
+                id, sides.x, sides.y
+            ),
+            LayoutError::NoRoom { id, unplaced } => write!(
+                f,
+                "Grid {} has no room for {} of its children",
+                id, unplaced
+            ),
+            LayoutError::DuplicateKey { key, first, second } => write!(
+                f,
+                "Key {} is used by both the widget at {:?} and the widget at {:?}",
+                key, first, second
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+///What a View does when part of its widget tree cannot be laid out
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "layout_files", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayoutPolicy {
+    ///Stop building. View::build panics, View::try_build returns the error.
+    #[default]
+    Panic,
+    ///Shrink overflowing children proportionally so that they fit, and clamp invalid sides to 0
+    Clamp,
+    ///Log the error and leave out the widgets that do not fit
+    Skip,
+}
+
+///State shared by all widgets during a single build of the widget tree
+pub struct BuildContext<'a> {
+    pub policy: LayoutPolicy,
+    ///errors that were recovered from during this build
+    pub errors: Vec<LayoutError>,
+    ///renderer the tree will be drawn with, used to measure text
+    pub renderer: &'a dyn Renderer,
+    ///fonts text widgets can refer to
+    pub fonts: &'a FontRegistry,
+    ///styles widgets draw with unless they override them. Widgets may change it while building their children,
+    ///and must put it back afterwards.
+    pub theme: Theme,
+    ///font size of each size group, found by the previous pass of the build
+    size_groups: HashMap<String, u16>,
+    ///smallest font size asked for by each size group's members during this pass
+    requested_sizes: HashMap<String, u16>,
+}
+
+///used when building without any fonts
+static NO_FONTS: FontRegistry = FontRegistry::new();
+
+impl<'a> BuildContext<'a> {
+    pub fn new(policy: LayoutPolicy, renderer: &'a dyn Renderer) -> Self {
+        BuildContext {
+            policy,
+            errors: Vec::new(),
+            renderer,
+            fonts: &NO_FONTS,
+            theme: Theme::default(),
+            size_groups: HashMap::new(),
+            requested_sizes: HashMap::new(),
+        }
+    }
+
+    ///Resolve font handles with fonts instead of an empty registry
+    pub fn fonts(self, fonts: &'a FontRegistry) -> Self {
+        BuildContext { fonts, ..self }
+    }
+
+    ///Style widgets with theme instead of the default one
+    pub fn theme(self, theme: Theme) -> Self {
+        BuildContext { theme, ..self }
+    }
+
+    ///Font size to use for a member of group that would pick font_size on its own. Until every member of the group
+    ///was built, this is font_size itself, see BuildContext::next_pass.
+    pub fn group_font_size(&mut self, group: &str, font_size: u16) -> u16 {
+        let requested = self
+            .requested_sizes
+            .entry(String::from(group))
+            .or_insert(font_size);
+        *requested = (*requested).min(font_size);
+        self.size_groups.get(group).copied().unwrap_or(font_size)
+    }
+
+    ///Prepare to build the tree again if some widgets can only be sized once the whole tree was built, like members
+    ///of size groups. Returns false if another pass isn't needed.
+    pub fn next_pass(&mut self) -> bool {
+        if self.requested_sizes.is_empty() || self.requested_sizes == self.size_groups {
+            return false;
+        }
+        self.size_groups = std::mem::take(&mut self.requested_sizes);
+        self.errors.clear();
+        true
+    }
+
+    ///Report an error. Returns the error back if the policy doesn't allow recovering from it,
+    ///otherwise records it so that the widget can carry on building.
+    pub fn report(&mut self, error: LayoutError) -> Result<(), LayoutError> {
+        match self.policy {
+            LayoutPolicy::Panic => return Err(error),
+            LayoutPolicy::Clamp => {}
+            LayoutPolicy::Skip => warn!("{}", error),
+        }
+        self.errors.push(error);
+        Ok(())
+    }
+}
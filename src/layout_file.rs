@@ -0,0 +1,396 @@
+/*
+Widget trees described in data files, so that menus can be changed without recompiling. A file describes a View's
+tree of columns, rows, buttons and texts in RON or JSON, with the same sides, colors, text, ids and keys that the
+builders take. Buttons name their callbacks instead of containing code. The names are looked up in a CallbackRegistry
+when the description is turned into a View, and every button shares the state handed to it.
+
+In RON, a button that starts the game could be described as
+
+    (
+        root: Column(children: [
+            Button(
+                sides: (90, 40),
+                color: "#f5f5dc",
+                on_press: "start",
+                child: Text(text: "Start", sides: (90, 90), color: "#ff0000"),
+            ),
+        ]),
+    )
+
+Optional fields can be left out, and are written without Some. The same description in JSON uses objects for the
+widgets, like {"Column": {"children": [...]}}, and arrays for sides.
+
+Only available with the layout_files feature.
+*/
+
+use super::button::{Button, Callback};
+use super::column::Column;
+use super::layout::LayoutPolicy;
+use super::row::Row;
+use super::text::Text;
+use super::{Geometry, Vector2, View, Widget};
+use macroquad::prelude::Color;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::path::Path;
+use std::rc::Rc;
+
+///Describes a View and its widget tree
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ViewDescription {
+    pub root: WidgetDescription,
+    #[serde(default)]
+    pub layout_policy: LayoutPolicy,
+}
+
+///Describes a widget and its children. Sides are percentages like the ones passed to Geometry::new, and default to
+///100% each way.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WidgetDescription {
+    Column {
+        #[serde(default)]
+        sides: Option<(f32, f32)>,
+        #[serde(default)]
+        id: u16,
+        #[serde(default)]
+        key: Option<String>,
+        #[serde(default)]
+        children: Vec<WidgetDescription>,
+    },
+    Row {
+        #[serde(default)]
+        sides: Option<(f32, f32)>,
+        #[serde(default)]
+        id: u16,
+        #[serde(default)]
+        key: Option<String>,
+        #[serde(default)]
+        children: Vec<WidgetDescription>,
+    },
+    Button {
+        #[serde(default)]
+        sides: Option<(f32, f32)>,
+        #[serde(default)]
+        id: u16,
+        #[serde(default)]
+        key: Option<String>,
+        #[serde(default)]
+        color: Option<HexColor>,
+        ///names of callbacks in the CallbackRegistry
+        #[serde(default)]
+        on_press: Option<String>,
+        #[serde(default)]
+        on_hover: Option<String>,
+        #[serde(default)]
+        on_unhover: Option<String>,
+        #[serde(default)]
+        child: Option<Box<WidgetDescription>>,
+    },
+    Text {
+        #[serde(default)]
+        sides: Option<(f32, f32)>,
+        #[serde(default)]
+        id: u16,
+        #[serde(default)]
+        key: Option<String>,
+        #[serde(default)]
+        color: Option<HexColor>,
+        text: String,
+    },
+}
+
+///A color written as "#rrggbb", or "#rrggbbaa" to give it an alpha
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct HexColor(pub Color);
+
+impl TryFrom<String> for HexColor {
+    type Error = String;
+
+    fn try_from(hex: String) -> Result<Self, Self::Error> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex.as_str());
+        let invalid = || format!("{} is not a color like #rrggbb or #rrggbbaa", hex);
+        //from_str_radix would also take signs
+        let hex_digits = digits.chars().all(|digit| digit.is_ascii_hexdigit());
+        if !(digits.len() == 6 || digits.len() == 8) || !hex_digits {
+            return Err(invalid());
+        }
+        let mut channels = [255u8; 4];
+        for (index, channel) in channels.iter_mut().enumerate().take(digits.len() / 2) {
+            *channel =
+                u8::from_str_radix(&digits[index * 2..index * 2 + 2], 16).map_err(|_| invalid())?;
+        }
+        let [r, g, b, a] = channels;
+        Ok(HexColor(Color::from_rgba(r, g, b, a)))
+    }
+}
+
+impl From<HexColor> for String {
+    fn from(color: HexColor) -> Self {
+        let [r, g, b, a]: [u8; 4] = color.0.into();
+        format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+    }
+}
+
+///Describes why a layout file could not be turned into a View
+#[derive(Clone, Debug, PartialEq)]
+pub enum LayoutFileError {
+    ///The file could not be read
+    Io(String),
+    ///The file isn't a valid description, with the parser's message
+    Parse(String),
+    ///A button names a callback that isn't in the registry
+    UnknownCallback(String),
+}
+
+impl fmt::Display for LayoutFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutFileError::Io(message) => write!(f, "Couldn't read layout file: {}", message),
+            LayoutFileError::Parse(message) => write!(f, "Invalid layout file: {}", message),
+            LayoutFileError::UnknownCallback(name) => {
+                write!(f, "No callback named {} is registered", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LayoutFileError {}
+
+type Handler<T> = Rc<RefCell<dyn FnMut(&mut Button<T>)>>;
+
+///Rust handlers for the callbacks named by buttons in layout files. The same handler can be named by any number of
+///buttons.
+pub struct CallbackRegistry<T> {
+    handlers: HashMap<String, Handler<T>>,
+}
+
+impl<T> Default for CallbackRegistry<T> {
+    fn default() -> Self {
+        CallbackRegistry {
+            handlers: HashMap::new(),
+        }
+    }
+}
+
+impl<T: 'static> CallbackRegistry<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Call handler for the buttons whose callbacks are named name
+    pub fn callback<F: FnMut(&mut Button<T>) + 'static>(mut self, name: &str, handler: F) -> Self {
+        self.handlers
+            .insert(String::from(name), Rc::new(RefCell::new(handler)));
+        self
+    }
+
+    ///Button callback calling the handler registered as name
+    fn get(&self, name: &str) -> Result<Callback<T>, LayoutFileError> {
+        let handler = self
+            .handlers
+            .get(name)
+            .ok_or_else(|| LayoutFileError::UnknownCallback(String::from(name)))?
+            .clone();
+        Ok(Box::new(move |button: &mut Button<T>| {
+            (handler.borrow_mut())(button)
+        }))
+    }
+}
+
+impl ViewDescription {
+    ///Parse a description written in RON
+    pub fn from_ron(source: &str) -> Result<Self, LayoutFileError> {
+        ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+            .from_str(source)
+            .map_err(|error| LayoutFileError::Parse(error.to_string()))
+    }
+
+    ///Parse a description written in JSON
+    pub fn from_json(source: &str) -> Result<Self, LayoutFileError> {
+        serde_json::from_str(source).map_err(|error| LayoutFileError::Parse(error.to_string()))
+    }
+
+    ///Read and parse a description from a file, as JSON if its extension is .json and as RON otherwise. Uses the
+    ///file system directly, so it doesn't work on the web.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, LayoutFileError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
+            .map_err(|error| LayoutFileError::Io(error.to_string()))?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Self::from_json(source.as_str()),
+            _ => Self::from_ron(source.as_str()),
+        }
+    }
+
+    ///Build the described View. Buttons share state and call the handlers callbacks has for the names they use.
+    pub fn instantiate<T: 'static>(
+        &self,
+        state: &Rc<RefCell<T>>,
+        callbacks: &CallbackRegistry<T>,
+    ) -> Result<View, LayoutFileError> {
+        let root = self.root.instantiate(state, callbacks)?;
+        Ok(View::new(root).layout_policy(self.layout_policy))
+    }
+}
+
+impl WidgetDescription {
+    ///Build the described widget and its children
+    pub fn instantiate<T: 'static>(
+        &self,
+        state: &Rc<RefCell<T>>,
+        callbacks: &CallbackRegistry<T>,
+    ) -> Result<Box<dyn Widget>, LayoutFileError> {
+        let geometry =
+            |sides: &Option<(f32, f32)>| sides.map(|(x, y)| Geometry::new(Vector2::new(x, y)));
+        let children = |children: &[WidgetDescription]| {
+            children
+                .iter()
+                .map(|child| child.instantiate(state, callbacks))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(match self {
+            WidgetDescription::Column {
+                sides,
+                id,
+                key,
+                children: described,
+            } => {
+                let mut column = Column::new().id(*id).children(children(described)?);
+                if let Some(geometry) = geometry(sides) {
+                    column = column.geometry(geometry);
+                }
+                if let Some(key) = key {
+                    column = column.key(key.as_str());
+                }
+                Box::new(column)
+            }
+            WidgetDescription::Row {
+                sides,
+                id,
+                key,
+                children: described,
+            } => {
+                let mut row = Row::new().id(*id).children(children(described)?);
+                if let Some(geometry) = geometry(sides) {
+                    row = row.geometry(geometry);
+                }
+                if let Some(key) = key {
+                    row = row.key(key.as_str());
+                }
+                Box::new(row)
+            }
+            WidgetDescription::Button {
+                sides,
+                id,
+                key,
+                color,
+                on_press,
+                on_hover,
+                on_unhover,
+                child,
+            } => {
+                let mut button = Button::default(state.clone()).id(*id);
+                if let Some(geometry) = geometry(sides) {
+                    button = button.geometry(geometry);
+                }
+                if let Some(key) = key {
+                    button = button.key(key.as_str());
+                }
+                if let Some(HexColor(color)) = color {
+                    button = button.color(*color);
+                }
+                if let Some(name) = on_press {
+                    button = button.is_pressed_callback(callbacks.get(name)?);
+                }
+                if let Some(name) = on_hover {
+                    button = button.is_hovered_callback(callbacks.get(name)?);
+                }
+                if let Some(name) = on_unhover {
+                    button = button.is_not_hovered_callback(callbacks.get(name)?);
+                }
+                if let Some(child) = child {
+                    button.set_child(child.instantiate(state, callbacks)?);
+                }
+                Box::new(button)
+            }
+            WidgetDescription::Text {
+                sides,
+                id,
+                key,
+                color,
+                text,
+            } => {
+                let mut widget = Text::default().id(*id).text(text.as_str());
+                if let Some(geometry) = geometry(sides) {
+                    widget = widget.geometry(geometry);
+                }
+                if let Some(key) = key {
+                    widget = widget.key(key.as_str());
+                }
+                if let Some(HexColor(color)) = color {
+                    widget = widget.color(*color);
+                }
+                Box::new(widget)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::RecordingRenderer;
+
+    const MENU: &str = r##"(
+        root: Column(children: [
+            Button(
+                sides: (90, 40),
+                id: 1,
+                on_press: "start",
+                child: Text(text: "Start", color: "#ff0000", key: "label"),
+            ),
+            Row(sides: (100, 60), children: [Text(text: "a", sides: (50, 100)), Text(text: "b", sides: (50, 100))]),
+        ]),
+        layout_policy: Clamp,
+    )"##;
+
+    #[test]
+    fn loads_ron_and_json_with_callbacks() {
+        let description = ViewDescription::from_ron(MENU).unwrap();
+        let json = serde_json::to_string(&description).unwrap();
+        assert_eq!(
+            ViewDescription::from_json(json.as_str()),
+            Ok(description.clone())
+        );
+
+        let presses = Rc::new(RefCell::new(0));
+        let callbacks = CallbackRegistry::new().callback("start", |button: &mut Button<u32>| {
+            *button.state.borrow_mut() += 1;
+        });
+        let mut view = description.instantiate(&presses, &callbacks).unwrap();
+        view.build_with(&RecordingRenderer::new(Vector2::new(800f32, 600f32)));
+        assert_eq!(view.root().child_count(), 2);
+        let label = view.find_by_key("label").unwrap().downcast_ref::<Text>();
+        assert_eq!(
+            label.unwrap().get_color(),
+            Color::new(1f32, 0f32, 0f32, 1f32)
+        );
+        view.find_by_id_mut(1).unwrap().activate();
+        assert_eq!(*presses.borrow(), 1);
+
+        let unknown = description.instantiate(&presses, &CallbackRegistry::new());
+        assert_eq!(
+            unknown.err(),
+            Some(LayoutFileError::UnknownCallback(String::from("start")))
+        );
+        let invalid = ViewDescription::from_ron(r#"(root: Text(text: "a", color: "red"))"#);
+        assert!(matches!(invalid, Err(LayoutFileError::Parse(_))));
+        let signed = ViewDescription::from_ron(r##"(root: Text(text: "a", color: "#+f+f+f"))"##);
+        assert!(matches!(signed, Err(LayoutFileError::Parse(_))));
+    }
+}
@@ -1,16 +1,108 @@
 pub extern crate macroquad;
 use macroquad::prelude::*;
+pub mod animation;
 pub mod button;
 pub mod column;
+pub mod event;
+pub mod focus;
+pub mod font;
+pub mod gamepad;
+pub mod grid;
+#[cfg(feature = "hot_reload")]
+pub mod hot_reload;
+pub mod key;
+pub mod layout;
+#[cfg(feature = "layout_files")]
+pub mod layout_file;
+pub mod render;
+pub mod router;
 pub mod row;
+pub mod snapshot;
 pub mod text;
+pub mod text_input;
+pub mod theme;
+pub mod tree;
+pub mod ui;
 
 use auto_impl::auto_impl;
+use key::Key;
+use std::any::Any;
 
+use self::event::{Event, Input};
+use self::focus::{Direction, FocusManager};
+use self::font::FontRegistry;
+use self::gamepad::{Gamepad, GamepadAction, GamepadSource};
+use self::layout::{BuildContext, LayoutError, LayoutPolicy};
+use self::render::{MacroquadRenderer, Renderer};
 use self::row::Row;
+use self::theme::Theme;
 
+///An application driven by run, which owns the frame loop. Only view is required, the other hooks do nothing unless
+///implemented.
 pub trait App {
-    fn tick(&mut self);
+    ///Called once before the first frame
+    fn init(&mut self) {}
+
+    ///Called at the start of every frame with the time since the previous frame, in seconds
+    fn update(&mut self, _dt: f32) {}
+
+    ///The View to show this frame. Called every frame after update, so returning a different View switches screens.
+    fn view(&mut self) -> &mut View;
+
+    ///Draw the frame, after the View returned by view was ticked. Draws that View unless implemented.
+    fn draw(&mut self) {
+        self.view().draw();
+    }
+
+    ///Called when the window changes size, with the new size. Views rebuild themselves to fit on their own.
+    fn on_resize(&mut self, _size: Vector2) {}
+
+    ///Return true to stop the frame loop after this frame
+    fn should_exit(&self) -> bool {
+        false
+    }
+
+    ///Called when the window is asked to close, which is also how the Android back button is reported. Return false
+    ///to keep running, for example after going back with Router::back.
+    fn on_quit_requested(&mut self) -> bool {
+        true
+    }
+
+    ///Called once when the frame loop stops, either because should_exit returned true or the window is being closed
+    fn on_exit(&mut self) {}
+}
+
+///Run app until it exits or its window is closed. Every frame, the app is updated, the View it returns is ticked, and
+///the app is drawn.
+pub async fn run<A: App>(mut app: A) {
+    //let the app know before the window closes
+    prevent_quit();
+    app.init();
+    let mut size = Vector2::new(screen_width(), screen_height());
+    loop {
+        let current = Vector2::new(screen_width(), screen_height());
+        if current != size {
+            size = current;
+            app.on_resize(size);
+        }
+        app.update(get_frame_time());
+        app.view().tick();
+        app.draw();
+        if finish_frame(&mut app, is_quit_requested()) {
+            break;
+        }
+        next_frame().await
+    }
+}
+
+///Whether the frame loop stops after this frame, because the app wants to exit or a quit was requested that the app
+///didn't cancel. Lets the app know with on_exit if it does.
+fn finish_frame<A: App>(app: &mut A, quit_requested: bool) -> bool {
+    let exit = app.should_exit() || (quit_requested && app.on_quit_requested());
+    if exit {
+        app.on_exit();
+    }
+    exit
 }
 
 ///Root of all other widgets. Represents the game window geometry.
@@ -19,6 +111,29 @@ pub struct View {
     pub geometry: Geometry,
     ///Top most widget in the current view
     child: Box<dyn Widget>,
+    ///What to do when the widget tree can't be laid out
+    policy: LayoutPolicy,
+    ///Errors recovered from during the last build
+    errors: Vec<LayoutError>,
+    ///Source of the events dispatched to the widget tree every tick
+    input: Input,
+    ///Keeps track of the widget focused with the keyboard
+    focus: FocusManager,
+    ///Optional gamepad used to move focus
+    gamepad: Option<Gamepad>,
+    ///Fonts the widgets in the view can use
+    fonts: FontRegistry,
+    ///Styles of the widgets in the view
+    theme: Theme,
+    ///Whether the tree must be rebuilt for a change made to the view itself, like a new theme
+    build: bool,
+    ///Events collected by the last tick that no widget handled
+    unhandled: Vec<Event>,
+    ///Whether tick dispatches input to the widget tree
+    input_enabled: bool,
+    ///Layout file the widget tree is reloaded from when it changes
+    #[cfg(feature = "hot_reload")]
+    hot_reload: Option<hot_reload::HotReload>,
 }
 
 impl Default for View {
@@ -26,6 +141,18 @@ impl Default for View {
         View {
             geometry: Geometry::new(Vector2::from(0, 0)),
             child: Box::new(Row::new()),
+            policy: LayoutPolicy::default(),
+            errors: Vec::new(),
+            input: Input::default(),
+            focus: FocusManager::default(),
+            gamepad: None,
+            fonts: FontRegistry::new(),
+            theme: Theme::default(),
+            build: false,
+            unhandled: Vec::new(),
+            input_enabled: true,
+            #[cfg(feature = "hot_reload")]
+            hot_reload: None,
         }
     }
 }
@@ -33,26 +160,217 @@ impl Default for View {
 // High level abstraction representing a single UI view
 impl View {
     #[cfg(feature = "debug_draw")]
-    pub fn debug_draw(&self) {
-        draw_circle(0f32, 0f32, 20f32, RED);
-        draw_circle(screen_width(), 0f32, 20f32, RED);
-        draw_circle(0f32, screen_height(), 20f32, RED);
-        draw_circle(screen_width(), screen_height(), 20f32, RED);
+    pub fn debug_draw(&self, renderer: &mut dyn Renderer) {
+        let size = renderer.screen_size();
+        renderer.draw_circle(0f32, 0f32, 20f32, RED);
+        renderer.draw_circle(size.x, 0f32, 20f32, RED);
+        renderer.draw_circle(0f32, size.y, 20f32, RED);
+        renderer.draw_circle(size.x, size.y, 20f32, RED);
     }
+
+    ///Draw the widget tree to the macroquad window
     pub fn draw(&self) {
+        self.draw_with(&mut MacroquadRenderer);
+    }
+
+    ///Draw the widget tree with the given renderer
+    pub fn draw_with(&self, renderer: &mut dyn Renderer) {
         #[cfg(feature = "debug_draw")]
-        self.debug_draw();
-        self.child.draw();
+        self.debug_draw(renderer);
+        self.child.draw(renderer);
+        self.focus.draw(self.child.as_ref(), renderer);
+        #[cfg(feature = "hot_reload")]
+        if let Some(hot_reload) = &self.hot_reload {
+            hot_reload.draw_error(renderer);
+        }
     }
 
     pub fn tick(&mut self) {
+        #[cfg(feature = "hot_reload")]
+        self.check_reload_with(&MacroquadRenderer);
+        self.animate(get_frame_time());
         // redraw if any child in the widget tree requests a rebuild or window is resized
-        if self.child.get_build() || self.resized() {
+        if self.build || self.child.get_build() || self.resized() {
+            self.build();
+        }
+        self.unhandled.clear();
+        //input is collected even while it's ignored, so that it doesn't pile up
+        let events = self.input.collect();
+        if self.input_enabled {
+            for event in events {
+                if !self.handle_event(&event) {
+                    self.unhandled.push(event);
+                }
+            }
+        }
+        self.handle_gamepad();
+        //rebuild right away if handling input changed the tree, so that it is drawn laid out this frame
+        if self.child.get_build() {
             self.build();
         }
         self.child.tick();
     }
 
+    ///Read the gamepad, if there is one, and move focus or activate the focused widget accordingly. Like other input,
+    ///the gamepad is read but ignored while input is disabled.
+    fn handle_gamepad(&mut self) {
+        let actions = match &mut self.gamepad {
+            Some(gamepad) => gamepad.poll(),
+            None => return,
+        };
+        if !self.input_enabled {
+            return;
+        }
+        for action in actions {
+            match action {
+                GamepadAction::Move(direction) => {
+                    self.focus.focus_nearest(self.child.as_mut(), direction)
+                }
+                GamepadAction::Activate => {
+                    self.focus.activate(self.child.as_mut());
+                }
+            }
+        }
+    }
+
+    ///Top most widget in the view
+    pub fn root(&self) -> &dyn Widget {
+        self.child.as_ref()
+    }
+
+    ///Keeps track of the widget focused with the keyboard
+    pub fn focus(&self) -> &FocusManager {
+        &self.focus
+    }
+
+    pub fn focus_mut(&mut self) -> &mut FocusManager {
+        &mut self.focus
+    }
+
+    ///Events collected by the last tick that no widget handled, unless they were already taken. Lets the owner of the
+    ///view react to input the view has no use for.
+    pub fn take_unhandled_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.unhandled)
+    }
+
+    ///Advance the animations running on every widget in the tree by dt seconds. tick does this with the frame time.
+    pub fn animate(&mut self, dt: f32) {
+        tree::visit_mut(self.child.as_mut(), &mut |_, widget| widget.animate(dt));
+    }
+
+    ///First widget in the tree with the given id, in drawing order. Downcast it with downcast_ref.
+    pub fn find_by_id(&self, id: u16) -> Option<&dyn Widget> {
+        tree::find_by_id(self.child.as_ref(), id)
+    }
+
+    ///First widget in the tree with the given id mutably, in drawing order. Changes that affect layout are picked up by
+    ///the next tick, for widgets that ask to be rebuilt when changed.
+    pub fn find_by_id_mut(&mut self, id: u16) -> Option<&mut dyn Widget> {
+        tree::find_by_id_mut(self.child.as_mut(), id)
+    }
+
+    ///Widget with the given key, or at the given path if the key is a Key::Path
+    pub fn find_by_key<K: Into<Key>>(&self, key: K) -> Option<&dyn Widget> {
+        tree::find_by_key(self.child.as_ref(), &key.into())
+    }
+
+    ///Widget with the given key mutably, or at the given path if the key is a Key::Path
+    pub fn find_by_key_mut<K: Into<Key>>(&mut self, key: K) -> Option<&mut dyn Widget> {
+        tree::find_by_key_mut(self.child.as_mut(), &key.into())
+    }
+
+    ///Call visitor on every widget in the tree with its path, in drawing order
+    pub fn visit(&self, visitor: &mut dyn FnMut(&[usize], &dyn Widget)) {
+        tree::visit(self.child.as_ref(), visitor);
+    }
+
+    ///Call visitor on every widget in the tree mutably with its path, in drawing order
+    pub fn visit_mut(&mut self, visitor: &mut dyn FnMut(&[usize], &mut dyn Widget)) {
+        tree::visit_mut(self.child.as_mut(), visitor);
+    }
+
+    ///View whose widget tree is loaded from a layout file, and loaded again whenever the file changes. Buttons share
+    ///state and call the handlers callbacks has for the names they use. The file is first loaded by the first tick.
+    #[cfg(feature = "hot_reload")]
+    pub fn from_file<T: 'static, P: AsRef<std::path::Path>>(
+        path: P,
+        state: std::rc::Rc<std::cell::RefCell<T>>,
+        callbacks: layout_file::CallbackRegistry<T>,
+    ) -> View {
+        View {
+            hot_reload: Some(hot_reload::HotReload::new(path, state, callbacks)),
+            ..View::new(column::Column::new())
+        }
+    }
+
+    ///Load the layout file the View was made from if it changed, and build the new tree to fit the screen of the
+    ///given renderer. tick does this every frame. The old tree is kept if the file can't be loaded or laid out, see
+    ///View::reload_error.
+    #[cfg(feature = "hot_reload")]
+    pub fn check_reload_with(&mut self, renderer: &dyn Renderer) {
+        let (root, policy) = match self.hot_reload.as_mut().and_then(|reload| reload.poll()) {
+            Some(loaded) => loaded,
+            None => return,
+        };
+        let old = std::mem::replace(&mut self.child, root);
+        let old_policy = std::mem::replace(&mut self.policy, policy);
+        hot_reload::restore_state(old.as_ref(), self.child.as_mut());
+        if let Err(error) = self.try_build_with(renderer) {
+            self.child = old;
+            self.policy = old_policy;
+            if let Some(reload) = &mut self.hot_reload {
+                reload.set_error(error.to_string());
+            }
+        }
+    }
+
+    ///Why the layout file the View was made from couldn't be loaded or laid out the last time it changed
+    #[cfg(feature = "hot_reload")]
+    pub fn reload_error(&self) -> Option<&str> {
+        self.hot_reload.as_ref().and_then(|reload| reload.error())
+    }
+
+    ///Stop or resume handling input in tick. Input that arrives while it is disabled is dropped. Enabled by default.
+    pub fn set_input_enabled(&mut self, input_enabled: bool) {
+        self.input_enabled = input_enabled;
+    }
+
+    ///Fonts the widgets in the view can use
+    pub fn fonts(&self) -> &FontRegistry {
+        &self.fonts
+    }
+
+    ///Load fonts with this before building, widgets refer to them by the handles it returns
+    pub fn fonts_mut(&mut self) -> &mut FontRegistry {
+        &mut self.fonts
+    }
+
+    ///Styles of the widgets in the view
+    pub fn get_theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    ///Restyle the widgets in the view with theme. The tree is rebuilt on the next tick.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.build = true;
+    }
+
+    ///Focus the widget at path from the root widget, or clear focus with None
+    pub fn set_focused(&mut self, path: Option<Vec<usize>>) {
+        self.focus.set_focused(self.child.as_mut(), path);
+    }
+
+    ///Dispatch an event through the widget tree. Returns true if a widget handled it. Pressing the pointer focuses
+    ///the focusable widget under it. Keys that no widget handles are used to move keyboard focus and activate the
+    ///focused widget.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        if let Event::PointerDown { position, .. } = *event {
+            self.focus.focus_at(self.child.as_mut(), position);
+        }
+        self.child.handle_event(event) || self.focus.handle_event(self.child.as_mut(), event)
+    }
+
     pub fn new<T: Widget + 'static>(child: T) -> View {
         View {
             geometry: Geometry {
@@ -74,28 +392,88 @@ impl View {
                 },
             },
             child: Box::new(child),
+            policy: LayoutPolicy::default(),
+            errors: Vec::new(),
+            input: Input::default(),
+            focus: FocusManager::default(),
+            gamepad: None,
+            fonts: FontRegistry::new(),
+            theme: Theme::default(),
+            build: false,
+            unhandled: Vec::new(),
+            input_enabled: true,
+            #[cfg(feature = "hot_reload")]
+            hot_reload: None,
         }
     }
 
+    ///Navigate the view with a gamepad, whose state is read from source every tick
+    pub fn gamepad<S: GamepadSource + 'static>(self, source: S) -> Self {
+        View {
+            gamepad: Some(Gamepad::new(source)),
+            ..self
+        }
+    }
+
+    ///Style the widgets in the view with theme instead of the default one
+    pub fn theme(self, theme: Theme) -> Self {
+        View { theme, ..self }
+    }
+
+    ///Set what happens when the widget tree can't be laid out. Panics by default.
+    pub fn layout_policy(self, policy: LayoutPolicy) -> Self {
+        View { policy, ..self }
+    }
+
+    ///Build the widget tree to fit the macroquad window, panicking if it can't be laid out under the current layout policy
     pub fn build(&mut self) {
-        self.geometry.abs_sides.x = screen_width();
-        self.geometry.abs_sides.y = screen_height();
-        self.geometry.top_left_curr = self.child.build(&self.geometry, None);
+        self.build_with(&MacroquadRenderer);
     }
 
-    pub fn resized(&self) -> bool {
-        if screen_height() == self.geometry.abs_sides.y
-            && screen_width() == self.geometry.abs_sides.x
-        {
-            false
-        } else {
-            true
+    ///Build the widget tree to fit the screen of the given renderer, panicking if it can't be laid out under the
+    ///current layout policy
+    pub fn build_with(&mut self, renderer: &dyn Renderer) {
+        if let Err(error) = self.try_build_with(renderer) {
+            panic!("{}", error);
+        }
+    }
+
+    ///Build the widget tree to fit the macroquad window. Errors are only returned with LayoutPolicy::Panic, other
+    ///policies recover from them and make them available through layout_errors.
+    pub fn try_build(&mut self) -> Result<(), LayoutError> {
+        self.try_build_with(&MacroquadRenderer)
+    }
+
+    ///Like try_build, but fits the screen of the given renderer and measures text with it
+    pub fn try_build_with(&mut self, renderer: &dyn Renderer) -> Result<(), LayoutError> {
+        self.geometry.abs_sides = renderer.screen_size();
+        self.build = false;
+        let mut ctx = BuildContext::new(self.policy, renderer)
+            .fonts(&self.fonts)
+            .theme(self.theme);
+        let mut result = self.child.build(&self.geometry, None, &mut ctx);
+        if result.is_ok() && ctx.next_pass() {
+            result = self.child.build(&self.geometry, None, &mut ctx);
         }
+        let result =
+            result.and_then(|end| tree::check_keys(self.child.as_ref(), &mut ctx).map(|_| end));
+        self.errors = ctx.errors;
+        self.geometry.top_left_curr = result?;
+        Ok(())
+    }
+
+    ///Errors that were recovered from during the last build
+    pub fn layout_errors(&self) -> &[LayoutError] {
+        &self.errors
+    }
+
+    pub fn resized(&self) -> bool {
+        screen_height() != self.geometry.abs_sides.y || screen_width() != self.geometry.abs_sides.x
     }
 }
 
 /// Represents a 2D vector value
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct Vector2 {
     pub x: f32,
     pub y: f32,
@@ -114,7 +492,7 @@ impl Vector2 {
 }
 
 /// Represents the four 2D directions
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Directions2D {
     pub top: f32,
     pub bottom: f32,
@@ -134,6 +512,7 @@ impl Directions2D {
 }
 
 ///Describes rectangle geometry assigned to a widget
+#[derive(Copy, Clone)]
 pub struct Geometry {
     //Only "public" data member
     ///length of sides in % of available space
@@ -157,35 +536,55 @@ pub struct Geometry {
 }
 
 impl Geometry {
+    ///Sides are checked when the widget is built, see Geometry::validate
     pub fn new(sides: Vector2) -> Self {
-        if sides.x < 0f32 || sides.y < 0f32 {
-            panic!("Widget has no geometry!");
-        } else {
-            Geometry {
-                top_left: Vector2::new(0f32, 0f32),
-                top_left_curr: Vector2::new(0f32, 0f32),
-                abs_sides: Vector2::new(0f32, 0f32),
-                sides,
-                margins: Directions2D::new(
-                    (100f32 - sides.y) / 2f32,
-                    (100f32 - sides.y) / 2f32,
-                    (100f32 - sides.x) / 2f32,
-                    (100f32 - sides.x) / 2f32,
-                ),
-                abs_margins: Directions2D::new(
-                    (100f32 - sides.y) / 2f32,
-                    (100f32 - sides.y) / 2f32,
-                    (100f32 - sides.x) / 2f32,
-                    (100f32 - sides.x) / 2f32,
-                ),
-            }
+        Geometry {
+            top_left: Vector2::new(0f32, 0f32),
+            top_left_curr: Vector2::new(0f32, 0f32),
+            abs_sides: Vector2::new(0f32, 0f32),
+            sides,
+            margins: Directions2D::new(
+                (100f32 - sides.y) / 2f32,
+                (100f32 - sides.y) / 2f32,
+                (100f32 - sides.x) / 2f32,
+                (100f32 - sides.x) / 2f32,
+            ),
+            abs_margins: Directions2D::new(
+                (100f32 - sides.y) / 2f32,
+                (100f32 - sides.y) / 2f32,
+                (100f32 - sides.x) / 2f32,
+                (100f32 - sides.x) / 2f32,
+            ),
         }
     }
+
+    ///Check that the sides can be laid out, and return the sides to lay the widget out with in this build. If the
+    ///layout policy recovers from invalid sides, they are clamped to 0 so that the widget takes up no space. The sides
+    ///and margins that were set are left as they are.
+    pub fn validate(&self, id: u16, ctx: &mut BuildContext) -> Result<Vector2, LayoutError> {
+        if self.sides.x >= 0f32 && self.sides.y >= 0f32 {
+            return Ok(self.sides);
+        }
+        ctx.report(LayoutError::InvalidGeometry {
+            id,
+            sides: self.sides,
+        })?;
+        Ok(Vector2::new(self.sides.x.max(0f32), self.sides.y.max(0f32)))
+    }
+
+    ///Whether point lies inside the widget, as laid out by the last build
+    pub fn contains(&self, point: Vector2) -> bool {
+        self.top_left.x < point.x
+            && point.x < self.top_left.x + self.abs_sides.x
+            && self.top_left.y < point.y
+            && point.y < self.top_left.y + self.abs_sides.y
+    }
 }
 
-#[auto_impl(&mut)]
+#[auto_impl(&mut, Box)]
 pub trait Widget {
-    fn draw(&self);
+    /// Draw the widget and its children. All drawing must go through renderer.
+    fn draw(&self, renderer: &mut dyn Renderer);
 
     /// Duties of build:
     /// 1. Set top left. The widget is drawn from the top left onwards.
@@ -194,17 +593,246 @@ pub trait Widget {
     /// 4. Call build on all children.
     /// 5. Return top left offset, ie, the top left starting point for the next widget to be drawn by the parent widget (it's next child).
     /// Assume the parent widget draws diagonally. This is so that the returing child widget is agnostic of what it's parent is (the parent may use horizontal, vertical, or both offsets.)
-    fn build(&mut self, geometry: &Geometry, margin: Option<Directions2D>) -> Vector2;
+    ///
+    /// Layout problems are reported to ctx, which decides whether building carries on or the error is returned.
+    fn build(
+        &mut self,
+        geometry: &Geometry,
+        margin: Option<Directions2D>,
+        ctx: &mut BuildContext,
+    ) -> Result<Vector2, LayoutError>;
     fn tick(&mut self);
 
+    /// Advance the animations running on the widget by dt seconds. Called on every widget in the tree by View::tick,
+    /// so containers don't pass it on to their children. Widgets whose geometry changed should ask to be rebuilt.
+    fn animate(&mut self, _dt: f32) {}
+
+    /// Handle an input event. Return true if the event was handled, so that it isn't passed on to siblings and ancestors.
+    /// Containers pass events on to their children, topmost first, before handling them themselves.
+    fn handle_event(&mut self, _event: &Event) -> bool {
+        false
+    }
+
     /// Get widget's absolute dimensions
     fn get_side(&self) -> Vector2;
 
+    /// Get the widget's geometry, as laid out by the last build
+    fn get_geometry(&self) -> &Geometry;
+
+    /// Short lowercase name of the kind of widget, like "row" or "button"
+    fn kind(&self) -> &'static str;
+
+    /// Number of child widgets
+    fn child_count(&self) -> usize {
+        0
+    }
+
+    /// Get a child widget by its position among the widget's children
+    fn child(&self, _index: usize) -> Option<&dyn Widget> {
+        None
+    }
+
+    /// Get a child widget mutably by its position among the widget's children
+    fn child_mut(&mut self, _index: usize) -> Option<&mut dyn Widget> {
+        None
+    }
+
+    /// Whether the child at index was laid out by the last build. Children left out by the layout policy are
+    /// neither drawn nor handed events, so they can't be focused either.
+    fn is_child_laid_out(&self, _index: usize) -> bool {
+        true
+    }
+
+    /// Index of the child next to the child at index from, in direction, following how the widget lays out
+    /// its children. Used to move keyboard focus with the arrow keys.
+    fn navigate(&self, _from: usize, _direction: Direction) -> Option<usize> {
+        None
+    }
+
+    /// Whether the widget can receive keyboard focus
+    fn is_focusable(&self) -> bool {
+        false
+    }
+
+    /// Called when the widget gains or loses keyboard focus
+    fn set_focused(&mut self, _focused: bool) {}
+
+    /// Take over the state of old, a widget of the same kind that this one replaces in a new version of the tree, like
+    /// the text typed into a text input. Used when reloading layout files.
+    fn restore_state(&mut self, _old: &dyn Widget) {}
+
+    /// Perform the widget's action, like a click does. Called when the widget is focused and Enter or Space is pressed.
+    fn activate(&mut self) {}
+
     /// Get the widget's ID. This has to be set manually by the user using the id method of the widget,
     ///and can be used to uniquely identify the widget when communicating with external functions like in the button callbacks.
     fn get_id(&self) -> u16;
 
+    /// Key identifying the widget within its View, set with the key method of the widget. Keys are checked to be
+    /// unique whenever the View is built. Widgets without one are identified by their path, see tree::keys.
+    fn get_key(&self) -> Option<&Key> {
+        None
+    }
+
     /// A widget may need an explicit rebuild even when the window isn't resized because
     /// for example it just got a new child that needs building
     fn get_build(&self) -> bool;
+
+    /// The widget as Any, to downcast it to its concrete type. Implement as `self`.
+    fn as_any(&self) -> &dyn Any;
+
+    /// The widget as Any mutably, to downcast it to its concrete type. Implement as `self`.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<'a> dyn Widget + 'a {
+    ///The widget as its concrete type W, or None if it is another kind of widget
+    pub fn downcast_ref<W: Widget + 'static>(&self) -> Option<&W> {
+        self.as_any().downcast_ref()
+    }
+
+    ///The widget as its concrete type W mutably, or None if it is another kind of widget
+    pub fn downcast_mut<W: Widget + 'static>(&mut self) -> Option<&mut W> {
+        self.as_any_mut().downcast_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::button::Button;
+    use crate::render::RecordingRenderer;
+    use crate::snapshot::snapshot;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    //a button in each cell of a two column grid, the first twice as wide as its cell so that the second covers it
+    fn overlapping_buttons(pressed: Rc<RefCell<Vec<u16>>>) -> View {
+        let button = |id: u16, key: &str, sides: Vector2| {
+            Button::default(pressed.clone())
+                .id(id)
+                .key(key)
+                .geometry(Geometry::new(sides))
+                .is_pressed_callback(move |button: &mut Button<Vec<u16>>| {
+                    button.state.borrow_mut().push(id);
+                })
+        };
+        let mut view = View::new(
+            grid::Grid::new(2, 1)
+                .push(button(1, "below", Vector2::from(200, 100)))
+                .push(button(2, "above", Vector2::from(100, 100))),
+        );
+        view.build_with(&RecordingRenderer::new(Vector2::new(800f32, 600f32)));
+        view
+    }
+
+    #[test]
+    fn pointer_events_go_to_the_topmost_widget_only() {
+        let pressed = Rc::new(RefCell::new(Vec::new()));
+        let mut view = overlapping_buttons(pressed.clone());
+        let button = MouseButton::Left;
+        let is_pressed = |view: &View, key: &str| {
+            let button = view.find_by_key(key).unwrap();
+            button
+                .downcast_ref::<Button<Vec<u16>>>()
+                .unwrap()
+                .get_state()
+                == button::ButtonState::Pressed
+        };
+
+        //both buttons are under the pointer, the one drawn last handles the press and the one below never sees it
+        let position = Vector2::new(500f32, 300f32);
+        assert!(view.handle_event(&Event::PointerDown { position, button }));
+        assert!(is_pressed(&view, "above"));
+        assert!(!is_pressed(&view, "below"));
+        assert!(view.handle_event(&Event::PointerUp { position, button }));
+        assert_eq!(*pressed.borrow(), vec![2]);
+
+        //only the button below is under the pointer
+        let position = Vector2::new(100f32, 300f32);
+        view.handle_event(&Event::PointerDown { position, button });
+        view.handle_event(&Event::PointerUp { position, button });
+        assert_eq!(*pressed.borrow(), vec![2, 1]);
+    }
+
+    struct Exiting {
+        view: View,
+        exit: bool,
+        //whether quit requests are cancelled
+        keep_running: bool,
+        exited: u32,
+    }
+
+    impl App for Exiting {
+        fn view(&mut self) -> &mut View {
+            &mut self.view
+        }
+
+        fn should_exit(&self) -> bool {
+            self.exit
+        }
+
+        fn on_quit_requested(&mut self) -> bool {
+            !self.keep_running
+        }
+
+        fn on_exit(&mut self) {
+            self.exited += 1;
+        }
+    }
+
+    #[test]
+    fn frame_loop_stops_when_the_app_exits_or_lets_a_quit_through() {
+        let mut app = Exiting {
+            view: View::new(column::Column::new()),
+            exit: false,
+            keep_running: true,
+            exited: 0,
+        };
+        assert!(!finish_frame(&mut app, false));
+        //the app cancels the quit
+        assert!(!finish_frame(&mut app, true));
+        assert_eq!(app.exited, 0);
+
+        app.keep_running = false;
+        assert!(finish_frame(&mut app, true));
+        assert_eq!(app.exited, 1);
+
+        app.keep_running = true;
+        app.exit = true;
+        assert!(finish_frame(&mut app, false));
+        assert_eq!(app.exited, 2);
+    }
+
+    #[test]
+    fn validate_clamps_invalid_sides_for_the_build_only() {
+        let renderer = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        let geometry = Geometry::new(Vector2::from(-10, 40));
+        let error = LayoutError::InvalidGeometry {
+            id: 3,
+            sides: Vector2::from(-10, 40),
+        };
+        let mut ctx = BuildContext::new(LayoutPolicy::Panic, &renderer);
+        assert_eq!(geometry.validate(3, &mut ctx), Err(error.clone()));
+        let mut ctx = BuildContext::new(LayoutPolicy::Clamp, &renderer);
+        assert_eq!(geometry.validate(3, &mut ctx), Ok(Vector2::from(0, 40)));
+        assert_eq!(ctx.errors, vec![error]);
+    }
+
+    #[test]
+    fn clamping_invalid_geometry_keeps_sides_and_margins() {
+        let state = Rc::new(RefCell::new(()));
+        let mut geometry = Geometry::new(Vector2::from(-10, 40));
+        geometry.margins = Directions2D::new(10f32, 0f32, 20f32, 0f32);
+        let mut view = View::new(Button::default(state).id(3).geometry(geometry))
+            .layout_policy(LayoutPolicy::Clamp);
+        assert_eq!(
+            snapshot(&mut view, Vector2::new(800f32, 600f32)).unwrap(),
+            "button id=3 top_left=(160.00, 60.00) abs_sides=(0.00, 240.00) \
+abs_margins=(60.00, 0.00, 160.00, 0.00)\n"
+        );
+        //only the build was clamped, the sides set stay so that they can be fixed later
+        assert_eq!(view.root().get_side(), Vector2::from(-10, 40));
+        assert_eq!(view.root().get_geometry().margins, geometry.margins);
+    }
 }
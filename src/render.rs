@@ -0,0 +1,557 @@
+use super::Vector2;
+use macroquad::prelude::*;
+use std::f32::consts::{FRAC_PI_2, PI};
+
+///Everything widgets draw goes through a Renderer, so that widget trees can be drawn without a window.
+///Coordinates are absolute screen coordinates, like the ones set on a widget's geometry by build.
+pub trait Renderer {
+    ///Size of the area being drawn to, used as the View's geometry when building
+    fn screen_size(&self) -> Vector2;
+
+    fn draw_rectangle(&mut self, x: f32, y: f32, w: f32, h: f32, color: Color);
+
+    fn draw_rectangle_lines(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        thickness: f32,
+        color: Color,
+    );
+
+    fn draw_line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, thickness: f32, color: Color);
+
+    fn draw_circle(&mut self, x: f32, y: f32, r: f32, color: Color);
+
+    fn draw_triangle(&mut self, a: Vector2, b: Vector2, c: Vector2, color: Color);
+
+    ///Draw text with its baseline starting at x, y
+    fn draw_text(&mut self, text: &str, x: f32, y: f32, params: TextParams);
+
+    ///Dimensions text would have when drawn. Used during build, so it must not depend on anything drawn.
+    fn measure_text(&self, text: &str, font: Option<Font>, font_size: u16) -> TextDimensions;
+
+    ///Draw texture stretched over the given rectangle, tinted by color
+    fn draw_texture(&mut self, texture: Texture2D, x: f32, y: f32, w: f32, h: f32, color: Color);
+
+    ///Draw a rectangle with corners rounded to radius, made of rectangles and a fan of triangles in each corner that
+    ///don't overlap, so that translucent colors blend evenly. The radius is limited to half the shorter side, and a
+    ///radius of 0 draws a plain rectangle.
+    fn draw_rounded_rectangle(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        radius: f32,
+        color: Color,
+    ) {
+        let r = radius.min(w / 2f32).min(h / 2f32);
+        if r <= 0f32 {
+            self.draw_rectangle(x, y, w, h, color);
+            return;
+        }
+        //the full width between the corners, then the strips above and below it between the corners
+        if h > 2f32 * r {
+            self.draw_rectangle(x, y + r, w, h - 2f32 * r, color);
+        }
+        if w > 2f32 * r {
+            self.draw_rectangle(x + r, y, w - 2f32 * r, r, color);
+            self.draw_rectangle(x + r, y + h - r, w - 2f32 * r, r, color);
+        }
+        //centers of the corners, with the angle their quarter circle starts at
+        let corners = [
+            (x + w - r, y + r, -FRAC_PI_2),
+            (x + w - r, y + h - r, 0f32),
+            (x + r, y + h - r, FRAC_PI_2),
+            (x + r, y + r, PI),
+        ];
+        let step = FRAC_PI_2 / CORNER_SEGMENTS as f32;
+        for &(center_x, center_y, start) in corners.iter() {
+            let center = Vector2::new(center_x, center_y);
+            let at =
+                |angle: f32| Vector2::new(center_x + r * angle.cos(), center_y + r * angle.sin());
+            for segment in 0..CORNER_SEGMENTS {
+                let angle = start + step * segment as f32;
+                self.draw_triangle(center, at(angle), at(angle + step), color);
+            }
+        }
+    }
+}
+
+///Triangles in the quarter circle of each rounded corner
+const CORNER_SEGMENTS: usize = 8;
+
+///Draws to the macroquad window. This is what View uses unless told otherwise.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MacroquadRenderer;
+
+impl Renderer for MacroquadRenderer {
+    fn screen_size(&self) -> Vector2 {
+        Vector2::new(screen_width(), screen_height())
+    }
+
+    fn draw_rectangle(&mut self, x: f32, y: f32, w: f32, h: f32, color: Color) {
+        draw_rectangle(x, y, w, h, color);
+    }
+
+    fn draw_rectangle_lines(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        thickness: f32,
+        color: Color,
+    ) {
+        draw_rectangle_lines(x, y, w, h, thickness, color);
+    }
+
+    fn draw_line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, thickness: f32, color: Color) {
+        draw_line(x1, y1, x2, y2, thickness, color);
+    }
+
+    fn draw_circle(&mut self, x: f32, y: f32, r: f32, color: Color) {
+        draw_circle(x, y, r, color);
+    }
+
+    fn draw_triangle(&mut self, a: Vector2, b: Vector2, c: Vector2, color: Color) {
+        draw_triangle(vec2(a.x, a.y), vec2(b.x, b.y), vec2(c.x, c.y), color);
+    }
+
+    fn draw_text(&mut self, text: &str, x: f32, y: f32, params: TextParams) {
+        draw_text_ex(text, x, y, params);
+    }
+
+    fn measure_text(&self, text: &str, font: Option<Font>, font_size: u16) -> TextDimensions {
+        measure_text(text, font, font_size, 1f32)
+    }
+
+    fn draw_texture(&mut self, texture: Texture2D, x: f32, y: f32, w: f32, h: f32, color: Color) {
+        draw_texture_ex(
+            texture,
+            x,
+            y,
+            color,
+            DrawTextureParams {
+                dest_size: Some(vec2(w, h)),
+                ..DrawTextureParams::default()
+            },
+        );
+    }
+}
+
+///A single call made to a RecordingRenderer
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCommand {
+    Rectangle {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        color: Color,
+    },
+    RectangleLines {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        thickness: f32,
+        color: Color,
+    },
+    Line {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        thickness: f32,
+        color: Color,
+    },
+    Circle {
+        x: f32,
+        y: f32,
+        r: f32,
+        color: Color,
+    },
+    Triangle {
+        a: Vector2,
+        b: Vector2,
+        c: Vector2,
+        color: Color,
+    },
+    Text {
+        text: String,
+        x: f32,
+        y: f32,
+        font: Font,
+        font_size: u16,
+        color: Color,
+    },
+    Texture {
+        texture: Texture2D,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        color: Color,
+    },
+}
+
+///Records draw calls in memory instead of drawing them. Needs no window or GL context, so widget trees can be
+///built and drawn on a headless machine. Text is measured with fixed metrics: every character is half as wide as
+///the font size, and the font size tall.
+pub struct RecordingRenderer {
+    ///size of the virtual screen
+    pub screen_size: Vector2,
+    ///every draw call made so far, in order
+    pub commands: Vec<DrawCommand>,
+}
+
+impl RecordingRenderer {
+    pub fn new(screen_size: Vector2) -> Self {
+        RecordingRenderer {
+            screen_size,
+            commands: Vec::new(),
+        }
+    }
+
+    ///Forget all commands recorded so far
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+}
+
+impl Renderer for RecordingRenderer {
+    fn screen_size(&self) -> Vector2 {
+        self.screen_size
+    }
+
+    fn draw_rectangle(&mut self, x: f32, y: f32, w: f32, h: f32, color: Color) {
+        self.commands
+            .push(DrawCommand::Rectangle { x, y, w, h, color });
+    }
+
+    fn draw_rectangle_lines(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        thickness: f32,
+        color: Color,
+    ) {
+        self.commands.push(DrawCommand::RectangleLines {
+            x,
+            y,
+            w,
+            h,
+            thickness,
+            color,
+        });
+    }
+
+    fn draw_line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, thickness: f32, color: Color) {
+        self.commands.push(DrawCommand::Line {
+            x1,
+            y1,
+            x2,
+            y2,
+            thickness,
+            color,
+        });
+    }
+
+    fn draw_circle(&mut self, x: f32, y: f32, r: f32, color: Color) {
+        self.commands.push(DrawCommand::Circle { x, y, r, color });
+    }
+
+    fn draw_triangle(&mut self, a: Vector2, b: Vector2, c: Vector2, color: Color) {
+        self.commands.push(DrawCommand::Triangle { a, b, c, color });
+    }
+
+    fn draw_text(&mut self, text: &str, x: f32, y: f32, params: TextParams) {
+        self.commands.push(DrawCommand::Text {
+            text: String::from(text),
+            x,
+            y,
+            font: params.font,
+            font_size: params.font_size,
+            color: params.color,
+        });
+    }
+
+    fn measure_text(&self, text: &str, _font: Option<Font>, font_size: u16) -> TextDimensions {
+        let size = font_size as f32;
+        TextDimensions {
+            width: text.chars().count() as f32 * size / 2f32,
+            height: size,
+            offset_y: size,
+        }
+    }
+
+    fn draw_texture(&mut self, texture: Texture2D, x: f32, y: f32, w: f32, h: f32, color: Color) {
+        self.commands.push(DrawCommand::Texture {
+            texture,
+            x,
+            y,
+            w,
+            h,
+            color,
+        });
+    }
+}
+
+///Offset, scale and opacity applied to everything drawn through a TransformedRenderer
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    ///moves everything by this much, after scaling
+    pub offset: Vector2,
+    ///scales everything about the center of the screen
+    pub scale: f32,
+    ///multiplies the alpha of every color
+    pub alpha: f32,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform {
+            offset: Vector2::new(0f32, 0f32),
+            scale: 1f32,
+            alpha: 1f32,
+        }
+    }
+}
+
+///Draws through another renderer with a Transform applied, for example to draw a whole View sliding or fading.
+///Text is measured untransformed, so that layouts don't change while they are drawn transformed.
+pub struct TransformedRenderer<'a> {
+    inner: &'a mut dyn Renderer,
+    transform: Transform,
+}
+
+impl<'a> TransformedRenderer<'a> {
+    pub fn new(inner: &'a mut dyn Renderer, transform: Transform) -> Self {
+        TransformedRenderer { inner, transform }
+    }
+
+    fn point(&self, x: f32, y: f32) -> (f32, f32) {
+        let size = self.inner.screen_size();
+        let (center_x, center_y) = (size.x / 2f32, size.y / 2f32);
+        (
+            center_x + (x - center_x) * self.transform.scale + self.transform.offset.x,
+            center_y + (y - center_y) * self.transform.scale + self.transform.offset.y,
+        )
+    }
+
+    fn color(&self, color: Color) -> Color {
+        Color {
+            a: color.a * self.transform.alpha,
+            ..color
+        }
+    }
+}
+
+impl Renderer for TransformedRenderer<'_> {
+    fn screen_size(&self) -> Vector2 {
+        self.inner.screen_size()
+    }
+
+    fn draw_rectangle(&mut self, x: f32, y: f32, w: f32, h: f32, color: Color) {
+        let (x, y) = self.point(x, y);
+        let scale = self.transform.scale;
+        let color = self.color(color);
+        self.inner.draw_rectangle(x, y, w * scale, h * scale, color);
+    }
+
+    fn draw_rectangle_lines(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        thickness: f32,
+        color: Color,
+    ) {
+        let (x, y) = self.point(x, y);
+        let scale = self.transform.scale;
+        let color = self.color(color);
+        self.inner
+            .draw_rectangle_lines(x, y, w * scale, h * scale, thickness * scale, color);
+    }
+
+    fn draw_line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, thickness: f32, color: Color) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x2, y2) = self.point(x2, y2);
+        let thickness = thickness * self.transform.scale;
+        let color = self.color(color);
+        self.inner.draw_line(x1, y1, x2, y2, thickness, color);
+    }
+
+    fn draw_circle(&mut self, x: f32, y: f32, r: f32, color: Color) {
+        let (x, y) = self.point(x, y);
+        let r = r * self.transform.scale;
+        let color = self.color(color);
+        self.inner.draw_circle(x, y, r, color);
+    }
+
+    fn draw_triangle(&mut self, a: Vector2, b: Vector2, c: Vector2, color: Color) {
+        let point = |point: Vector2| {
+            let (x, y) = self.point(point.x, point.y);
+            Vector2::new(x, y)
+        };
+        let (a, b, c) = (point(a), point(b), point(c));
+        let color = self.color(color);
+        self.inner.draw_triangle(a, b, c, color);
+    }
+
+    fn draw_text(&mut self, text: &str, x: f32, y: f32, params: TextParams) {
+        let (x, y) = self.point(x, y);
+        let params = TextParams {
+            font_size: (params.font_size as f32 * self.transform.scale) as u16,
+            color: self.color(params.color),
+            ..params
+        };
+        self.inner.draw_text(text, x, y, params);
+    }
+
+    fn measure_text(&self, text: &str, font: Option<Font>, font_size: u16) -> TextDimensions {
+        self.inner.measure_text(text, font, font_size)
+    }
+
+    fn draw_texture(&mut self, texture: Texture2D, x: f32, y: f32, w: f32, h: f32, color: Color) {
+        let (x, y) = self.point(x, y);
+        let scale = self.transform.scale;
+        let color = self.color(color);
+        self.inner
+            .draw_texture(texture, x, y, w * scale, h * scale, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::button::Button;
+    use crate::text::Text;
+    use crate::{Geometry, View};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    //rectangles and texts recorded, leaving out what debug_draw adds
+    fn drawn(renderer: RecordingRenderer) -> Vec<DrawCommand> {
+        renderer
+            .commands
+            .into_iter()
+            .filter(|command| {
+                matches!(
+                    command,
+                    DrawCommand::Rectangle { .. } | DrawCommand::Text { .. }
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn records_a_tree_in_drawing_order() {
+        let mut view = View::new(
+            Button::default(Rc::new(RefCell::new(())))
+                .geometry(Geometry::new(Vector2::from(40, 40)))
+                .color(BLUE)
+                .text_color(BLACK)
+                .child(Text::default().text("hi")),
+        );
+        let mut renderer = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        view.build_with(&renderer);
+        view.draw_with(&mut renderer);
+        let commands = drawn(renderer);
+        assert_eq!(commands.len(), 2);
+        assert_eq!(
+            commands[0],
+            DrawCommand::Rectangle {
+                x: 240f32,
+                y: 180f32,
+                w: 320f32,
+                h: 240f32,
+                color: BLUE
+            }
+        );
+        //the text fills the button's height and is centered in its width, each character being half as wide as tall
+        match &commands[1] {
+            DrawCommand::Text {
+                text,
+                x,
+                y,
+                font_size,
+                color,
+                ..
+            } => assert_eq!(
+                (text.as_str(), *x, *y, *font_size, *color),
+                ("hi", 280f32, 420f32, 240, BLACK)
+            ),
+            command => panic!("expected text, got {:?}", command),
+        }
+    }
+
+    #[test]
+    fn transforms_positions_sizes_and_colors() {
+        let mut renderer = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        let transform = Transform {
+            offset: Vector2::new(10f32, 20f32),
+            scale: 0.5f32,
+            alpha: 0.5f32,
+        };
+        let mut transformed = TransformedRenderer::new(&mut renderer, transform);
+        transformed.draw_rectangle(200f32, 100f32, 100f32, 50f32, WHITE);
+        let params = TextParams {
+            font_size: 20,
+            color: BLACK,
+            ..TextParams::default()
+        };
+        transformed.draw_text("a", 400f32, 300f32, params);
+        //scaled about the center of the screen, then offset
+        let commands = drawn(renderer);
+        assert_eq!(
+            commands[0],
+            DrawCommand::Rectangle {
+                x: 310f32,
+                y: 220f32,
+                w: 50f32,
+                h: 25f32,
+                color: Color::new(1f32, 1f32, 1f32, 0.5f32)
+            }
+        );
+        match &commands[1] {
+            DrawCommand::Text {
+                x,
+                y,
+                font_size,
+                color,
+                ..
+            } => assert_eq!(
+                (*x, *y, *font_size, *color),
+                (410f32, 320f32, 10, Color::new(0f32, 0f32, 0f32, 0.5f32))
+            ),
+            command => panic!("expected text, got {:?}", command),
+        }
+    }
+
+    #[test]
+    fn rounded_rectangle_parts_dont_overlap() {
+        let mut renderer = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        renderer.draw_rounded_rectangle(10f32, 10f32, 100f32, 40f32, 10f32, WHITE);
+        //the parts cover exactly the rounded rectangle, short of the slivers the triangles cut off the circles
+        let area: f32 = renderer
+            .commands
+            .iter()
+            .map(|command| match *command {
+                DrawCommand::Rectangle { w, h, .. } => w * h,
+                DrawCommand::Triangle { a, b, c, .. } => {
+                    ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)).abs() / 2f32
+                }
+                _ => 0f32,
+            })
+            .sum();
+        let rounded = 100f32 * 40f32 - (4f32 - PI) * 10f32 * 10f32;
+        assert!(area <= rounded && area > rounded - 5f32, "{}", area);
+    }
+}
@@ -0,0 +1,516 @@
+/*
+Navigation between Views. A Router keeps a stack of Views, each made by a named route. Only the View on top is ticked
+and drawn, the ones under it keep their state until they are on top again. Widget callbacks navigate through a
+RouterHandle, whose commands are carried out the next time the router is updated, so that the View running the
+callback isn't replaced while it is handling input.
+
+Changing the View on top can be animated with a Transition, which draws both the View being covered and the one
+covering it. Popping plays the transition backwards. Only the View on top handles input, and not until its transition
+has finished.
+*/
+
+use super::animation::Easing;
+use super::event::Event;
+use super::focus::Direction;
+use super::render::{MacroquadRenderer, Renderer, Transform, TransformedRenderer};
+use super::{Vector2, View};
+use macroquad::logging::warn;
+use macroquad::miniquad::KeyMods;
+use macroquad::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+enum Command {
+    Push(String),
+    Pop,
+    Replace(String),
+}
+
+///Queues navigation for a Router. Cheap to clone, so that every callback that navigates can have its own.
+#[derive(Clone)]
+pub struct RouterHandle {
+    commands: Rc<RefCell<Vec<Command>>>,
+}
+
+impl RouterHandle {
+    ///Put a new View made by the named route on top of the stack
+    pub fn push(&self, name: &str) {
+        self.commands
+            .borrow_mut()
+            .push(Command::Push(String::from(name)));
+    }
+
+    ///Remove the View on top of the stack, going back to the one under it
+    pub fn pop(&self) {
+        self.commands.borrow_mut().push(Command::Pop);
+    }
+
+    ///Replace the View on top of the stack with a new one made by the named route
+    pub fn replace(&self, name: &str) {
+        self.commands
+            .borrow_mut()
+            .push(Command::Replace(String::from(name)));
+    }
+}
+
+///How a View covering another is animated
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransitionKind {
+    ///The new View fades in over the old one
+    Fade,
+    ///The new View pushes the old one off the screen, both moving in direction
+    Slide(Direction),
+    ///The new View grows and fades in over the old one
+    Scale,
+}
+
+///Animation played when the View on top of a Router changes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transition {
+    pub kind: TransitionKind,
+    ///length of the animation in seconds
+    pub duration: f32,
+    pub easing: Easing,
+}
+
+impl Transition {
+    pub fn new(kind: TransitionKind, duration: f32) -> Self {
+        Transition {
+            kind,
+            duration,
+            easing: Easing::default(),
+        }
+    }
+
+    pub fn easing(self, easing: Easing) -> Self {
+        Transition { easing, ..self }
+    }
+
+    ///Transforms to draw the View being covered and the View covering it with, when the animation is at progress,
+    ///from 0 to 1, on a screen of the given size
+    pub fn transforms(&self, progress: f32, screen_size: Vector2) -> (Transform, Transform) {
+        let progress = self.easing.apply(progress);
+        match self.kind {
+            TransitionKind::Fade => (
+                Transform::default(),
+                Transform {
+                    alpha: progress,
+                    ..Transform::default()
+                },
+            ),
+            TransitionKind::Slide(direction) => {
+                let distance = match direction {
+                    Direction::Up => Vector2::new(0f32, -screen_size.y),
+                    Direction::Down => Vector2::new(0f32, screen_size.y),
+                    Direction::Left => Vector2::new(-screen_size.x, 0f32),
+                    Direction::Right => Vector2::new(screen_size.x, 0f32),
+                };
+                let at = |progress: f32| Transform {
+                    offset: Vector2::new(distance.x * progress, distance.y * progress),
+                    ..Transform::default()
+                };
+                (at(progress), at(progress - 1f32))
+            }
+            TransitionKind::Scale => (
+                Transform::default(),
+                Transform {
+                    scale: 0.5f32 + progress / 2f32,
+                    alpha: progress,
+                    ..Transform::default()
+                },
+            ),
+        }
+    }
+}
+
+///A transition being played
+struct Running {
+    transition: Transition,
+    ///seconds since it started
+    elapsed: f32,
+    ///View taken off the stack that is still being drawn, by a pop or a replace
+    removed: Option<View>,
+    ///whether the removed View is the one covering the View on top, rather than the one being covered
+    popped: bool,
+}
+
+///A stack of named Views. Escape pops the View on top when none of its widgets handle it, but never the last one.
+///Other ways of going back, like the Android back button, call back.
+#[derive(Default)]
+pub struct Router {
+    ///functions making the View of each route
+    routes: HashMap<String, Box<dyn FnMut() -> View>>,
+    ///route names and their Views, the last one is on top
+    stack: Vec<(String, View)>,
+    ///commands queued by handles
+    commands: Rc<RefCell<Vec<Command>>>,
+    ///animation played when the View on top changes, None to switch instantly
+    transition: Option<Transition>,
+    running: Option<Running>,
+}
+
+impl Router {
+    pub fn new() -> Self {
+        Router::default()
+    }
+
+    ///Add a route. make is called to make a new View every time the route is pushed.
+    pub fn route<F: FnMut() -> View + 'static>(mut self, name: &str, make: F) -> Self {
+        self.routes.insert(String::from(name), Box::new(make));
+        self
+    }
+
+    ///Animate changing the View on top with transition
+    pub fn transition(self, transition: Transition) -> Self {
+        Router {
+            transition: Some(transition),
+            ..self
+        }
+    }
+
+    ///Handle to navigate with from widget callbacks
+    pub fn handle(&self) -> RouterHandle {
+        RouterHandle {
+            commands: self.commands.clone(),
+        }
+    }
+
+    ///Put a new View made by the named route on top of the stack. Unknown routes are logged and ignored.
+    pub fn push(&mut self, name: &str) {
+        if let Some(view) = self.make(name) {
+            self.stack.push((String::from(name), view));
+            self.start(None, false);
+        }
+    }
+
+    ///Remove the View on top of the stack, unless it is the only one. Returns true if a View was removed.
+    pub fn pop(&mut self) -> bool {
+        if self.stack.len() < 2 {
+            return false;
+        }
+        let removed = self.stack.pop().map(|(_, view)| view);
+        self.start(removed, true);
+        true
+    }
+
+    ///Go back as if Escape was pressed: the View on top handles it if one of its widgets wants to, otherwise it is
+    ///popped. Returns false if there was nowhere to go back to, so that the app can quit instead.
+    ///
+    ///macroquad has no key for the Android back button, it asks the app to quit instead. To go back with it, call back
+    ///from App::on_quit_requested.
+    pub fn back(&mut self) -> bool {
+        self.handle_event(&Event::KeyDown {
+            key: KeyCode::Escape,
+            modifiers: KeyMods::default(),
+        })
+    }
+
+    ///Replace the View on top of the stack with a new one made by the named route
+    pub fn replace(&mut self, name: &str) {
+        if let Some(view) = self.make(name) {
+            let removed = self.stack.pop().map(|(_, view)| view);
+            self.stack.push((String::from(name), view));
+            self.start(removed, false);
+        }
+    }
+
+    ///Whether a transition is being played
+    pub fn is_transitioning(&self) -> bool {
+        self.running.is_some()
+    }
+
+    ///Name of the route on top of the stack
+    pub fn current(&self) -> Option<&str> {
+        self.stack.last().map(|(name, _)| name.as_str())
+    }
+
+    ///Number of Views on the stack
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    ///The View on top of the stack. Panics if nothing was pushed yet.
+    pub fn view(&mut self) -> &mut View {
+        match self.stack.last_mut() {
+            Some((_, view)) => view,
+            None => panic!("Router has no views, push a route first"),
+        }
+    }
+
+    ///Carry out navigation queued by handles, go back if the View on top didn't handle Escape during its last tick,
+    ///and advance the transition being played by dt seconds. Call once per frame before ticking the View on top, like
+    ///App::update.
+    pub fn update(&mut self, dt: f32) {
+        if let Some(running) = &mut self.running {
+            running.elapsed += dt;
+            if running.elapsed >= running.transition.duration {
+                self.finish();
+            }
+        }
+        let back = match self.stack.last_mut() {
+            Some((_, view)) => view.take_unhandled_events().iter().any(is_back),
+            None => false,
+        };
+        if back {
+            self.pop();
+        }
+        self.navigate();
+    }
+
+    ///Update the router, then tick the View on top
+    pub fn tick(&mut self) {
+        self.update(get_frame_time());
+        self.view().tick();
+    }
+
+    ///Draw the View on top to the macroquad window, along with the View it is covering during a transition
+    pub fn draw(&self) {
+        self.draw_with(&mut MacroquadRenderer);
+    }
+
+    ///Draw the View on top with the given renderer, along with the View it is covering during a transition
+    pub fn draw_with(&self, renderer: &mut dyn Renderer) {
+        let running = match &self.running {
+            Some(running) => running,
+            None => {
+                if let Some((_, view)) = self.stack.last() {
+                    view.draw_with(renderer);
+                }
+                return;
+            }
+        };
+        let top = self.stack.last().map(|(_, view)| view);
+        //popping plays the transition that pushed the removed View backwards
+        let progress = running.elapsed / running.transition.duration;
+        let (covered, covering, progress) = match (&running.removed, running.popped) {
+            (Some(removed), true) => (top, Some(removed), 1f32 - progress),
+            (Some(removed), false) => (Some(removed), top, progress),
+            (None, _) => (
+                self.stack.iter().rev().nth(1).map(|(_, view)| view),
+                top,
+                progress,
+            ),
+        };
+        let (covered_transform, covering_transform) = running
+            .transition
+            .transforms(progress, renderer.screen_size());
+        if let Some(view) = covered {
+            view.draw_with(&mut TransformedRenderer::new(renderer, covered_transform));
+        }
+        if let Some(view) = covering {
+            view.draw_with(&mut TransformedRenderer::new(renderer, covering_transform));
+        }
+    }
+
+    ///Dispatch an event to the View on top, going back if it is an unhandled Escape. Returns true if the event was
+    ///handled. Navigation queued while handling the event is carried out right away.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        //the View on top ignores input until its transition has finished
+        let mut handled = match self.stack.last_mut() {
+            Some((_, view)) if self.running.is_none() => view.handle_event(event),
+            _ => false,
+        };
+        if !handled && is_back(event) {
+            handled = self.pop();
+        }
+        self.navigate();
+        handled
+    }
+
+    fn make(&mut self, name: &str) -> Option<View> {
+        match self.routes.get_mut(name) {
+            Some(make) => Some(make()),
+            None => {
+                warn!("No route named {}", name);
+                None
+            }
+        }
+    }
+
+    ///Start playing the transition after the View on top changed, if there is one. The View on top ignores input
+    ///until it has finished.
+    fn start(&mut self, removed: Option<View>, popped: bool) {
+        //a transition that is still playing is cut short
+        self.finish();
+        if let Some(transition) = self.transition.filter(|t| t.duration > 0f32) {
+            self.running = Some(Running {
+                transition,
+                elapsed: 0f32,
+                removed,
+                popped,
+            });
+            if let Some((_, view)) = self.stack.last_mut() {
+                view.set_input_enabled(false);
+            }
+        }
+    }
+
+    ///Stop playing the transition, and let the View on top handle input again
+    fn finish(&mut self) {
+        self.running = None;
+        if let Some((_, view)) = self.stack.last_mut() {
+            view.set_input_enabled(true);
+        }
+    }
+
+    ///Carry out the commands queued by handles, in order
+    fn navigate(&mut self) {
+        let commands = std::mem::take(&mut *self.commands.borrow_mut());
+        for command in commands {
+            match command {
+                Command::Push(name) => self.push(name.as_str()),
+                Command::Pop => {
+                    self.pop();
+                }
+                Command::Replace(name) => self.replace(name.as_str()),
+            }
+        }
+    }
+}
+
+///Whether event asks to go back
+fn is_back(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::KeyDown {
+            key: KeyCode::Escape,
+            ..
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::button::Button;
+    use crate::column::Column;
+    use crate::render::{DrawCommand, RecordingRenderer};
+    use crate::{Geometry, Vector2};
+
+    //a view with a single button filling the screen, which navigates to route when pressed
+    fn view(handle: RouterHandle, route: &'static str, presses: Rc<RefCell<u32>>) -> View {
+        let mut view = View::new(
+            Column::new().push(
+                Button::default(Rc::new(RefCell::new(handle)))
+                    .geometry(Geometry::new(Vector2::from(100, 100)))
+                    .is_pressed_callback(move |button: &mut Button<RouterHandle>| {
+                        *presses.borrow_mut() += 1;
+                        button.state.borrow().push(route);
+                    }),
+            ),
+        );
+        view.build_with(&RecordingRenderer::new(Vector2::new(800f32, 600f32)));
+        view
+    }
+
+    //press and release the pointer in the middle of the screen, returns whether both were handled
+    fn click(router: &mut Router) -> bool {
+        let position = Vector2::new(400f32, 300f32);
+        let button = MouseButton::Left;
+        let pressed = router.handle_event(&Event::PointerDown { position, button });
+        let released = router.handle_event(&Event::PointerUp { position, button });
+        pressed && released
+    }
+
+    fn escape() -> Event {
+        Event::KeyDown {
+            key: KeyCode::Escape,
+            modifiers: KeyMods::default(),
+        }
+    }
+
+    #[test]
+    fn pushes_from_callbacks_and_pops_on_escape() {
+        let menu_presses = Rc::new(RefCell::new(0));
+        let router = Router::new();
+        let handle = router.handle();
+        let presses = menu_presses.clone();
+        let mut router = router
+            .route("menu", move || {
+                view(handle.clone(), "game", presses.clone())
+            })
+            .route("game", || View::new(Column::new()));
+        router.push("menu");
+
+        click(&mut router);
+        assert_eq!(router.current(), Some("game"));
+        assert_eq!(router.depth(), 2);
+        assert!(router.handle_event(&escape()));
+        assert_eq!(router.current(), Some("menu"));
+        //the menu under the game wasn't made again
+        click(&mut router);
+        assert_eq!(*menu_presses.borrow(), 2);
+
+        router.replace("menu");
+        assert_eq!(router.current(), Some("menu"));
+        assert_eq!(router.depth(), 2);
+        assert!(router.pop());
+        assert!(!router.pop());
+        assert_eq!(router.depth(), 1);
+        //the last view is never popped
+        assert!(!router.handle_event(&escape()));
+        assert_eq!(router.current(), Some("menu"));
+    }
+
+    #[test]
+    fn back_pops_unless_on_the_last_view() {
+        let mut router = Router::new()
+            .route("menu", || View::new(Column::new()))
+            .route("settings", || View::new(Column::new()));
+        router.push("menu");
+        router.push("settings");
+        assert!(router.back());
+        assert_eq!(router.current(), Some("menu"));
+        //nowhere to go back to, the app should quit
+        assert!(!router.back());
+        assert_eq!(router.depth(), 1);
+    }
+
+    fn menu_x(renderer: &RecordingRenderer) -> Option<f32> {
+        renderer.commands.iter().find_map(|command| match command {
+            DrawCommand::Rectangle { x, .. } => Some(*x),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn draws_both_views_during_transition() {
+        let router = Router::new();
+        let handle = router.handle();
+        let presses = Rc::new(RefCell::new(0));
+        let mut router = router
+            .route("menu", move || {
+                view(handle.clone(), "game", presses.clone())
+            })
+            .route("game", || View::new(Column::new()))
+            .transition(
+                Transition::new(TransitionKind::Slide(Direction::Left), 1f32)
+                    .easing(Easing::Linear),
+            );
+        router.push("menu");
+        router.update(1f32);
+        click(&mut router);
+        assert!(router.is_transitioning());
+        //clicks are ignored until the game has slid in
+        assert!(!click(&mut router));
+
+        router.update(0.25f32);
+        let mut renderer = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        router.draw_with(&mut renderer);
+        //the menu's button, drawn first as the view being covered
+        assert_eq!(menu_x(&renderer), Some(-200f32));
+
+        //going back plays the transition backwards
+        router.update(1f32);
+        assert!(!router.is_transitioning());
+        router.pop();
+        router.update(0.25f32);
+        let mut renderer = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        router.draw_with(&mut renderer);
+        //the menu's button, drawn first as the view being covered
+        assert_eq!(menu_x(&renderer), Some(-600f32));
+        router.update(1f32);
+        assert!(click(&mut router));
+    }
+}
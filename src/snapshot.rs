@@ -0,0 +1,142 @@
+/*
+Helpers for testing layouts without a window. A View is built against a virtual screen using a RecordingRenderer, and
+the geometry of every widget in the tree is dumped in a stable format that can be compared against a stored snapshot.
+Numbers are rounded to two decimals so that snapshots don't change with insignificant floating point differences.
+*/
+
+use super::layout::LayoutError;
+use super::render::RecordingRenderer;
+use super::{Directions2D, Vector2, View, Widget};
+use std::fmt::Write;
+
+///Build view against a virtual screen of the given size and describe the resulting layout as text. Each widget is
+///on its own line, indented by two spaces per level of depth:
+///
+///`button id=1 top_left=(240.00, 40.00) abs_sides=(320.00, 240.00) abs_margins=(40.00, 0.00, 240.00, 240.00)`
+///
+///Margins are listed as top, bottom, left, right.
+pub fn snapshot(view: &mut View, screen_size: Vector2) -> Result<String, LayoutError> {
+    view.try_build_with(&RecordingRenderer::new(screen_size))?;
+    let mut out = String::new();
+    write_text(view.root(), 0, &mut out);
+    Ok(out)
+}
+
+///Like snapshot, but describes the layout as JSON. Every widget is an object with the keys kind, id, top_left,
+///abs_sides, abs_margins and children.
+pub fn snapshot_json(view: &mut View, screen_size: Vector2) -> Result<String, LayoutError> {
+    view.try_build_with(&RecordingRenderer::new(screen_size))?;
+    let mut out = String::new();
+    write_json(view.root(), 0, &mut out);
+    out.push('\n');
+    Ok(out)
+}
+
+fn write_text(widget: &dyn Widget, depth: usize, out: &mut String) {
+    let geometry = widget.get_geometry();
+    writeln!(
+        out,
+        "{}{} id={} top_left={} abs_sides={} abs_margins={}",
+        "  ".repeat(depth),
+        widget.kind(),
+        widget.get_id(),
+        vector(geometry.top_left),
+        vector(geometry.abs_sides),
+        directions(geometry.abs_margins),
+    )
+    .unwrap();
+    for index in 0..widget.child_count() {
+        if let Some(child) = widget.child(index) {
+            write_text(child, depth + 1, out);
+        }
+    }
+}
+
+fn write_json(widget: &dyn Widget, depth: usize, out: &mut String) {
+    let geometry = widget.get_geometry();
+    let indent = "  ".repeat(depth + 1);
+    writeln!(out, "{{").unwrap();
+    writeln!(out, "{}\"kind\": \"{}\",", indent, widget.kind()).unwrap();
+    writeln!(out, "{}\"id\": {},", indent, widget.get_id()).unwrap();
+    writeln!(
+        out,
+        "{}\"top_left\": [{:.2}, {:.2}],",
+        indent, geometry.top_left.x, geometry.top_left.y
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "{}\"abs_sides\": [{:.2}, {:.2}],",
+        indent, geometry.abs_sides.x, geometry.abs_sides.y
+    )
+    .unwrap();
+    let margins = geometry.abs_margins;
+    writeln!(
+        out,
+        "{}\"abs_margins\": [{:.2}, {:.2}, {:.2}, {:.2}],",
+        indent, margins.top, margins.bottom, margins.left, margins.right
+    )
+    .unwrap();
+    write!(out, "{}\"children\": [", indent).unwrap();
+    let count = widget.child_count();
+    for index in 0..count {
+        if let Some(child) = widget.child(index) {
+            write!(out, "{}", if index == 0 { "" } else { ", " }).unwrap();
+            write_json(child, depth + 1, out);
+        }
+    }
+    write!(out, "]\n{}}}", "  ".repeat(depth)).unwrap();
+}
+
+fn vector(vector: Vector2) -> String {
+    format!("({:.2}, {:.2})", vector.x, vector.y)
+}
+
+fn directions(directions: Directions2D) -> String {
+    format!(
+        "({:.2}, {:.2}, {:.2}, {:.2})",
+        directions.top, directions.bottom, directions.left, directions.right
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::button::Button;
+    use crate::text::Text;
+    use crate::Geometry;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn json_nests_children() {
+        let mut view = View::new(
+            Button::default(Rc::new(RefCell::new(())))
+                .geometry(Geometry::new(Vector2::from(50, 50)))
+                .child(
+                    Text::default()
+                        .text("abcd")
+                        .geometry(Geometry::new(Vector2::from(90, 90))),
+                ),
+        );
+        assert_eq!(
+            snapshot_json(&mut view, Vector2::new(800f32, 600f32)).unwrap(),
+            r#"{
+  "kind": "button",
+  "id": 0,
+  "top_left": [200.00, 150.00],
+  "abs_sides": [400.00, 300.00],
+  "abs_margins": [150.00, 150.00, 200.00, 200.00],
+  "children": [{
+    "kind": "text",
+    "id": 0,
+    "top_left": [220.00, 165.00],
+    "abs_sides": [360.00, 270.00],
+    "abs_margins": [15.00, 15.00, 20.00, 20.00],
+    "children": []
+  }]
+}
+"#
+        );
+    }
+}
@@ -1,30 +1,151 @@
 use macroquad::prelude::*;
 
+use super::animation::{Animations, Easing, Tween};
+use super::font::FontHandle;
+use super::key::Key;
+use super::layout::{BuildContext, LayoutError};
+use super::render::Renderer;
+use super::theme::{Style, Theme};
 use super::{Directions2D, Geometry, Vector2, Widget};
+use std::any::Any;
 
-pub struct Text {
-    //offset the text by this much when rendering to center it within it's parent widget
-    offset: Vector2,
+///Horizontal position of text within its box
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum HAlign {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+impl HAlign {
+    ///Offset from the left of space at which text of the given width starts
+    fn offset(self, space: f32, width: f32) -> f32 {
+        match self {
+            HAlign::Left => 0f32,
+            HAlign::Center => (space - width) / 2f32,
+            HAlign::Right => space - width,
+        }
+    }
+}
+
+///Vertical position of text within its box
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum VAlign {
+    Top,
+    #[default]
+    Middle,
+    Bottom,
+    ///Center a line of the font, and put the text's baseline where that line's would be. Unlike Middle, the baseline
+    ///doesn't move with the shape of the letters, so labels of the same size line up. Centers wrapped text like Middle.
+    Baseline,
+}
+
+impl VAlign {
+    ///Offset from the top of space at which a block of the given height starts
+    fn top(self, space: f32, height: f32) -> f32 {
+        match self {
+            VAlign::Top => 0f32,
+            VAlign::Middle | VAlign::Baseline => (space - height) / 2f32,
+            VAlign::Bottom => space - height,
+        }
+    }
 
+    ///Offset from the top of space of the baseline of a single line of text. line is the size of a line of the font.
+    fn baseline(self, space: f32, text: TextDimensions, line: TextDimensions) -> f32 {
+        match self {
+            VAlign::Baseline => self.top(space, line.height) + line.offset_y,
+            _ => self.top(space, text.height) + text.offset_y,
+        }
+    }
+}
+
+///How Text picks its font size
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum FontSizing {
+    ///The largest size at which the text fits in its box
+    #[default]
+    Fit,
+    ///Always this size, whether the text fits or not
+    Fixed(u16),
+    ///The size that fits, but no smaller than min and no larger than max
+    Clamped { min: u16, max: u16 },
+}
+
+///Which part of the text to cut out when it doesn't fit, replaced by an ellipsis
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Truncate {
+    Start,
+    Middle,
+    End,
+}
+
+pub struct Text {
+    ///the full text, even if only part of it is shown
     text: String,
     font_size: u16,
+    sizing: FontSizing,
+    ///texts in the same group are all drawn at the smallest font size any of them picks
+    size_group: Option<String>,
     geometry: Geometry,
-    color: Color,
+    ///color to draw with instead of the theme's text color
+    color: Option<Color>,
+    ///style taken from the View's theme in the last build
+    style: Style,
+    ///multiplies the alpha of the color, from 0 to 1
+    opacity: f32,
+    ///tweens running on the color and opacity
+    animations: Animations,
+    ///color of the running color tween, drawn instead of color until the tween finishes
+    animated_color: Option<Color>,
     _resize_to_parent: bool,
+    ///font to draw with, or the theme's if None
+    font: Option<FontHandle>,
+    ///font the handle resolved to in the last build, None for macroquad's built in font
+    resolved: Option<Font>,
+    halign: HAlign,
+    valign: VAlign,
+    ///break the text into lines on word boundaries and newlines instead of fitting it on one line
+    wrap: bool,
+    ///distance between the baselines of wrapped lines, as a multiple of the font size
+    line_height: f32,
+    ///font size is never picked smaller than this, text that doesn't fit at it is truncated instead
+    min_font_size: u16,
+    ///how to shorten text that doesn't fit at the chosen font size. None lets it overflow.
+    truncate: Option<Truncate>,
+    ///lines of text as shown, and where their baselines start relative to the top left, as laid out by the last build
+    lines: Vec<(String, Vector2)>,
     id: u16,
+    ///identifies the widget within its View, see Widget::get_key
+    key: Option<Key>,
     build: bool,
 }
 
 impl Default for Text {
     fn default() -> Self {
         Text {
-            offset: Vector2::from(0, 0),
             geometry: Geometry::new(Vector2::new(100f32, 100f32)),
-            color: WHITE,
+            color: None,
+            style: Theme::default().text,
+            opacity: 1f32,
+            animations: Animations::default(),
+            animated_color: None,
             _resize_to_parent: true,
             text: String::new(),
             font_size: 10,
+            sizing: FontSizing::default(),
+            size_group: None,
+            font: None,
+            resolved: None,
+            halign: HAlign::default(),
+            valign: VAlign::default(),
+            wrap: false,
+            line_height: 1f32,
+            min_font_size: 1,
+            truncate: None,
+            lines: Vec::new(),
             id: 0,
+            key: None,
             build: false,
         }
     }
@@ -36,7 +157,14 @@ impl Text {
     }
 
     pub fn color(self, color: Color) -> Self {
-        Text { color, ..self }
+        Text {
+            color: Some(color),
+            ..self
+        }
+    }
+
+    pub fn opacity(self, opacity: f32) -> Self {
+        Text { opacity, ..self }
     }
 
     pub fn resize_to_parent(self, _resize_to_parent: bool) -> Self {
@@ -53,9 +181,304 @@ impl Text {
         }
     }
 
+    ///How the font size is picked. Fits the text to its box by default.
+    pub fn sizing(self, sizing: FontSizing) -> Self {
+        Text { sizing, ..self }
+    }
+
+    ///Draw the text at the same font size as every other text in group, the smallest any of them picks. Keeps
+    ///labels of different lengths, like those of a menu's buttons, the same size.
+    pub fn size_group(self, group: &str) -> Self {
+        Text {
+            size_group: Some(String::from(group)),
+            ..self
+        }
+    }
+
+    ///Never pick a font size smaller than min_font_size, so that the text stays readable. Text that doesn't fit at
+    ///this size overflows, unless it is truncated.
+    pub fn min_font_size(self, min_font_size: u16) -> Self {
+        Text {
+            min_font_size,
+            ..self
+        }
+    }
+
+    ///Cut out part of text that doesn't fit at the chosen font size, and show an ellipsis in its place
+    pub fn truncate(self, truncate: Truncate) -> Self {
+        Text {
+            truncate: Some(truncate),
+            ..self
+        }
+    }
+
+    ///Font from the View's font registry to draw the text with
+    pub fn font(self, font: FontHandle) -> Self {
+        Text {
+            font: Some(font),
+            ..self
+        }
+    }
+
+    ///Where to put the text horizontally when it doesn't fill the width of its box. Centered by default.
+    pub fn halign(self, halign: HAlign) -> Self {
+        Text { halign, ..self }
+    }
+
+    ///Where to put the text vertically when it doesn't fill the height of its box. Centered by default.
+    pub fn valign(self, valign: VAlign) -> Self {
+        Text { valign, ..self }
+    }
+
+    ///Wrap the text over as many lines as it takes to show it at the largest font size that fits
+    pub fn wrap(self, wrap: bool) -> Self {
+        Text { wrap, ..self }
+    }
+
+    ///Distance between the baselines of wrapped lines, as a multiple of the font size. 1 by default.
+    pub fn line_height(self, line_height: f32) -> Self {
+        Text {
+            line_height,
+            ..self
+        }
+    }
+
     pub fn id(self, id: u16) -> Self {
         Self { id, ..self }
     }
+
+    ///Key identifying the widget within its View. Must be unique.
+    pub fn key<K: Into<Key>>(self, key: K) -> Self {
+        Self {
+            key: Some(key.into()),
+            ..self
+        }
+    }
+
+    ///The whole text, including any part cut out by truncation. Useful for tooltips.
+    pub fn full_text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    ///Largest font size at which the text fits in dimensions on one line
+    fn fit_line(&self, dimensions: Vector2, renderer: &dyn Renderer) -> u16 {
+        //find the constraint for fitting text. Could be width or height.
+        let text_dimensions = renderer.measure_text(self.text.as_str(), self.resolved, 1);
+        let height_ratio: f32 = dimensions.y / text_dimensions.height;
+        let width_ratio: f32 = dimensions.x / text_dimensions.width;
+        height_ratio.min(width_ratio) as u16
+    }
+
+    ///Align the text on one line at the current font size within dimensions, truncating it if it doesn't fit
+    fn align_line(&mut self, dimensions: Vector2, renderer: &dyn Renderer) {
+        let measure = |text: &str| renderer.measure_text(text, self.resolved, self.font_size);
+        let shown = match self.truncate {
+            Some(truncate) if measure(self.text.as_str()).width > dimensions.x => {
+                shorten(self.text.as_str(), truncate, dimensions.x, &|text| {
+                    measure(text).width
+                })
+            }
+            _ => self.text.clone(),
+        };
+        //measure at the chosen size, text doesn't scale exactly with the font size
+        let text_dimensions = measure(shown.as_str());
+        let line = measure("Ag");
+        let offset = Vector2::new(
+            self.halign.offset(dimensions.x, text_dimensions.width),
+            self.valign.baseline(dimensions.y, text_dimensions, line),
+        );
+        self.lines = vec![(shown, offset)];
+    }
+
+    ///Largest font size at which the wrapped text fits in dimensions, or 1 if it doesn't fit at all
+    fn fit_wrapped(&self, dimensions: Vector2, renderer: &dyn Renderer) -> u16 {
+        let fits = |font_size: u16| {
+            let lines = wrap(
+                self.text.as_str(),
+                dimensions.x,
+                self.resolved,
+                font_size,
+                renderer,
+            );
+            let height = font_size as f32 * (1f32 + (lines.len() as f32 - 1f32) * self.line_height);
+            height <= dimensions.y && lines.iter().all(|(_, width)| *width <= dimensions.x)
+        };
+        //binary search, since bigger fonts never take up less space
+        let (mut low, mut high) = (1u16, dimensions.y.max(1f32) as u16);
+        let mut best = 1;
+        while low <= high {
+            let font_size = low + (high - low) / 2;
+            if fits(font_size) {
+                best = font_size;
+                low = font_size + 1;
+            } else {
+                high = font_size - 1;
+            }
+        }
+        best
+    }
+
+    ///Wrap the text at the current font size, and align the lines within dimensions
+    fn wrap_lines(&mut self, dimensions: Vector2, renderer: &dyn Renderer) {
+        let mut lines = wrap(
+            self.text.as_str(),
+            dimensions.x,
+            self.resolved,
+            self.font_size,
+            renderer,
+        );
+        let font_size = self.font_size as f32;
+        let advance = font_size * self.line_height;
+        if let Some(truncate) = self.truncate {
+            let measure = |text: &str| {
+                renderer
+                    .measure_text(text, self.resolved, self.font_size)
+                    .width
+            };
+            //drop the lines below the box, and end the last one left with an ellipsis
+            let fitting = (((dimensions.y - font_size) / advance).floor() as usize + 1).max(1);
+            if lines.len() > fitting {
+                lines.truncate(fitting);
+                let last = &mut lines[fitting - 1];
+                let ended = format!("{}\u{2026}", last.0);
+                last.0 = if measure(ended.as_str()) <= dimensions.x {
+                    ended
+                } else {
+                    shorten(last.0.as_str(), Truncate::End, dimensions.x, &measure)
+                };
+                last.1 = measure(last.0.as_str());
+            }
+            //words too long for a line of their own
+            for (line, width) in lines.iter_mut() {
+                if *width > dimensions.x {
+                    *line = shorten(line.as_str(), truncate, dimensions.x, &measure);
+                    *width = measure(line.as_str());
+                }
+            }
+        }
+        //align each line horizontally, and the block of lines vertically
+        let height = font_size + (lines.len() as f32 - 1f32) * advance;
+        let ascent = renderer
+            .measure_text("Ag", self.resolved, self.font_size)
+            .offset_y;
+        let top = self.valign.top(dimensions.y, height);
+        self.lines = lines
+            .into_iter()
+            .enumerate()
+            .map(|(index, (line, width))| {
+                let offset = Vector2::new(
+                    self.halign.offset(dimensions.x, width),
+                    top + index as f32 * advance + ascent,
+                );
+                (line, offset)
+            })
+            .collect();
+    }
+}
+
+//Setters and getters for Text
+impl Text {
+    ///Change the text, laying it out again on the next tick
+    pub fn set_text(&mut self, text: &str) {
+        self.text = String::from(text);
+        self.build = true;
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = Some(color);
+    }
+
+    ///Go back to drawing the text in the theme's text color
+    pub fn clear_color(&mut self) {
+        self.color = None;
+    }
+
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity;
+    }
+
+    ///Fade the color from where it is to color over duration seconds. When the fade is over, the text is drawn in its
+    ///own or the theme's color again.
+    pub fn animate_color(&mut self, color: Color, duration: f32, easing: Easing) {
+        self.animations.color = Some(Tween::new(self.get_color(), color, duration, easing));
+    }
+
+    ///Fade from the current opacity to opacity over duration seconds
+    pub fn animate_opacity(&mut self, opacity: f32, duration: f32, easing: Easing) {
+        self.animations.opacity = Some(Tween::new(self.opacity, opacity, duration, easing));
+    }
+
+    ///Color the text is drawn with, the fading color while animate_color runs, otherwise its own or the theme's
+    pub fn get_color(&self) -> Color {
+        self.animated_color
+            .or(self.color)
+            .unwrap_or(self.style.text_color)
+    }
+
+    pub fn get_opacity(&self) -> f32 {
+        self.opacity
+    }
+}
+
+///Cut characters out of text at the place given by truncate, and put an ellipsis there, so that it is no wider than
+///width. Keeps as many characters as fit. Only the ellipsis is left if nothing else fits.
+fn shorten(text: &str, truncate: Truncate, width: f32, measure: &dyn Fn(&str) -> f32) -> String {
+    let characters: Vec<char> = text.chars().collect();
+    let shortened = |kept: usize| -> String {
+        let (start, end) = match truncate {
+            Truncate::Start => (0, kept),
+            Truncate::Middle => (kept - kept / 2, kept / 2),
+            Truncate::End => (kept, 0),
+        };
+        let mut shortened: String = characters[..start].iter().collect();
+        shortened.push('\u{2026}');
+        shortened.extend(&characters[characters.len() - end..]);
+        shortened
+    };
+    //binary search the number of characters kept, fewer characters are never wider
+    let (mut low, mut high) = (0, characters.len());
+    while low < high {
+        let kept = low + (high - low).div_ceil(2);
+        if measure(shortened(kept).as_str()) <= width {
+            low = kept;
+        } else {
+            high = kept - 1;
+        }
+    }
+    shortened(low)
+}
+
+///Break text into lines no wider than width at font_size, on newlines and between words. Words that are wider than
+///width on their own get a line to themselves. Returns every line with its width.
+fn wrap(
+    text: &str,
+    width: f32,
+    font: Option<Font>,
+    font_size: u16,
+    renderer: &dyn Renderer,
+) -> Vec<(String, f32)> {
+    let measure = |line: &str| renderer.measure_text(line, font, font_size).width;
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if line.is_empty() {
+                line.push_str(word);
+                continue;
+            }
+            let longer = format!("{} {}", line, word);
+            if measure(longer.as_str()) <= width {
+                line = longer;
+            } else {
+                let full = std::mem::replace(&mut line, String::from(word));
+                let full_width = measure(full.as_str());
+                lines.push((full, full_width));
+            }
+        }
+        let line_width = measure(line.as_str());
+        lines.push((line, line_width));
+    }
+    lines
 }
 
 impl Widget for Text {
@@ -66,32 +489,53 @@ impl Widget for Text {
         self.id
     }
 
-    fn build(&mut self, geometry: &Geometry, margins: Option<Directions2D>) -> Vector2 {
+    fn get_key(&self) -> Option<&Key> {
+        self.key.as_ref()
+    }
+
+    fn build(
+        &mut self,
+        geometry: &Geometry,
+        margins: Option<Directions2D>,
+        ctx: &mut BuildContext,
+    ) -> Result<Vector2, LayoutError> {
+        self.build = false;
+        let sides = self.geometry.validate(self.id, ctx)?;
         //Find original dimensions of parent and
         //calculate dimensions of this widget from parent dimensions and positions from margins
         let dimensions = Vector2 {
-            x: geometry.abs_sides.x * self.geometry.sides.x / 100f32,
-            y: geometry.abs_sides.y * self.geometry.sides.y / 100f32,
+            x: geometry.abs_sides.x * sides.x / 100f32,
+            y: geometry.abs_sides.y * sides.y / 100f32,
         };
 
         self.geometry.abs_sides = dimensions;
 
-        //find the constraint for fitting text. Could be width or height.
-        let text_dimensions = measure_text(self.text.as_str(), None, 1, 1f32);
-        let height_ratio: f32 = dimensions.y / text_dimensions.height;
-        let width_ratio: f32 = dimensions.x / text_dimensions.width;
-
-        self.font_size = if width_ratio > height_ratio {
-            //height is the constraining factor
-            self.offset.y = 0f32;
-            self.offset.x = self.geometry.abs_sides.x / 2f32 - text_dimensions.width / 2f32;
-            height_ratio as u16
-        } else {
-            //width is
-            self.offset.x = 0f32;
-            self.offset.y = self.geometry.abs_sides.y / 2f32 - text_dimensions.height / 2f32;
-            width_ratio as u16
+        self.style = ctx.theme.text;
+        self.resolved = ctx
+            .fonts
+            .resolve(self.font.or(self.style.font), self.text.as_str());
+        let fit = || {
+            if self.wrap {
+                self.fit_wrapped(dimensions, ctx.renderer)
+            } else {
+                self.fit_line(dimensions, ctx.renderer)
+            }
+        };
+        let font_size = match self.sizing {
+            FontSizing::Fit => fit(),
+            FontSizing::Fixed(font_size) => font_size,
+            FontSizing::Clamped { min, max } => fit().clamp(min, max),
+        };
+        let font_size = font_size.max(self.min_font_size);
+        self.font_size = match &self.size_group {
+            Some(group) => ctx.group_font_size(group.as_str(), font_size),
+            None => font_size,
         };
+        if self.wrap {
+            self.wrap_lines(dimensions, ctx.renderer);
+        } else {
+            self.align_line(dimensions, ctx.renderer);
+        }
 
         let margins = margins.unwrap_or(Directions2D {
             top: geometry.abs_sides.y * self.geometry.margins.top / 100f32,
@@ -107,30 +551,209 @@ impl Widget for Text {
         };
         self.geometry.top_left = tl;
 
-        Vector2 {
+        Ok(Vector2 {
             x: geometry.top_left_curr.x + margins.left + dimensions.x + margins.right,
             y: geometry.top_left_curr.y + margins.top + dimensions.y + margins.bottom,
-        }
+        })
     }
 
     fn tick(&mut self) {}
 
-    fn draw(&self) {
-        draw_text_ex(
-            self.text.as_str(),
-            //Text drawing from the bottom left instead of top left. Add offsets to center the text.
-            //For other types of justification, change the offset calculation in the build function.
-            self.geometry.top_left.x + self.offset.x,
-            self.geometry.top_left.y - self.offset.y + self.geometry.abs_sides.y,
-            TextParams {
-                font_size: self.font_size,
-                color: self.color,
-                ..TextParams::default()
-            },
-        );
+    fn animate(&mut self, dt: f32) {
+        let mut color = self.get_color();
+        //only the color and opacity of text are animated, so it never needs rebuilding
+        self.animations
+            .advance(dt, &mut color, &mut self.geometry, &mut self.opacity);
+        //once the fade is over the text's own or theme's color shows again
+        self.animated_color = self.animations.color.map(|_| color);
+    }
+
+    fn draw(&self, renderer: &mut dyn Renderer) {
+        let color = self.get_color();
+        let color = Color {
+            a: color.a * self.opacity,
+            ..color
+        };
+        for (line, offset) in &self.lines {
+            renderer.draw_text(
+                line.as_str(),
+                //Text is drawn from its baseline instead of its top left. The offset is set by build to align the text.
+                self.geometry.top_left.x + offset.x,
+                self.geometry.top_left.y + offset.y,
+                TextParams {
+                    font: self.resolved.unwrap_or_default(),
+                    font_size: self.font_size,
+                    color,
+                    ..TextParams::default()
+                },
+            );
+        }
     }
 
     fn get_side(&self) -> Vector2 {
         self.geometry.sides
     }
+
+    fn get_geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+
+    fn kind(&self) -> &'static str {
+        "text"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::LayoutPolicy;
+    use crate::render::{DrawCommand, RecordingRenderer};
+
+    //builds text as the whole of a 200x100 screen
+    fn drawn_lines(text: Text) -> Vec<(String, f32, f32, u16)> {
+        let mut text = text;
+        let mut renderer = RecordingRenderer::new(Vector2::new(200f32, 100f32));
+        let mut ctx = BuildContext::new(LayoutPolicy::Panic, &renderer);
+        let mut screen = Geometry::new(Vector2::new(100f32, 100f32));
+        screen.abs_sides = renderer.screen_size;
+        text.build(&screen, None, &mut ctx).unwrap();
+        text.draw(&mut renderer);
+        renderer
+            .commands
+            .into_iter()
+            .filter_map(|command| match command {
+                DrawCommand::Text {
+                    text,
+                    x,
+                    y,
+                    font_size,
+                    ..
+                } => Some((text, x, y, font_size)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn wraps_at_largest_size_that_fits() {
+        //on one line the text would be 10 pixels tall, on two lines of 9 characters it fits at 44
+        let lines = drawn_lines(Text::default().text("the quick brown fox").wrap(true));
+        assert_eq!(
+            lines,
+            vec![
+                (String::from("the quick"), 1f32, 50f32, 44),
+                (String::from("brown fox"), 1f32, 94f32, 44),
+            ]
+        );
+    }
+
+    #[test]
+    fn breaks_on_newlines_with_line_height() {
+        let lines = drawn_lines(
+            Text::default()
+                .text("a b\nc")
+                .wrap(true)
+                .line_height(1.5f32),
+        );
+        let text: Vec<&str> = lines.iter().map(|line| line.0.as_str()).collect();
+        assert_eq!(text, vec!["a b", "c"]);
+        let font_size = lines[0].3 as f32;
+        assert_eq!(lines[1].2 - lines[0].2, font_size * 1.5f32);
+    }
+
+    #[test]
+    fn aligns_within_box() {
+        //the width constrains "abcdef" to 66, which leaves space above and below it in the 200x100 box
+        let lines = drawn_lines(Text::default().text("abcdef"));
+        assert_eq!(lines[0], (String::from("abcdef"), 1f32, 83f32, 66));
+        let lines = drawn_lines(Text::default().text("abcdef").valign(VAlign::Top));
+        assert_eq!(lines[0].2, 66f32);
+        let lines = drawn_lines(
+            Text::default()
+                .text("a\nb")
+                .wrap(true)
+                .halign(HAlign::Right)
+                .valign(VAlign::Bottom),
+        );
+        assert_eq!(
+            lines,
+            vec![
+                (String::from("a"), 175f32, 50f32, 50),
+                (String::from("b"), 175f32, 100f32, 50),
+            ]
+        );
+    }
+
+    #[test]
+    fn fixed_and_clamped_sizes() {
+        let lines = drawn_lines(Text::default().text("ab").sizing(FontSizing::Fixed(12)));
+        assert_eq!(lines[0].3, 12);
+        let lines = drawn_lines(
+            Text::default()
+                .text("abcdef")
+                .sizing(FontSizing::Clamped { min: 80, max: 90 }),
+        );
+        assert_eq!(lines[0].3, 80);
+    }
+
+    #[test]
+    fn size_group_shares_smallest_size() {
+        use crate::row::Row;
+        use crate::View;
+        let label = |text: &str| {
+            Text::default()
+                .text(text)
+                .size_group("menu")
+                .geometry(Geometry::new(Vector2::from(50, 100)))
+        };
+        let mut view = View::new(Row::new().push(label("play")).push(label("settings")));
+        view.build_with(&RecordingRenderer::new(Vector2::new(800f32, 200f32)));
+        let mut renderer = RecordingRenderer::new(Vector2::new(800f32, 200f32));
+        view.draw_with(&mut renderer);
+        let sizes: Vec<u16> = renderer
+            .commands
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::Text { font_size, .. } => Some(*font_size),
+                _ => None,
+            })
+            .collect();
+        //"settings" fits its 400 pixels at 100, "play" alone would fit at 200
+        assert_eq!(sizes, vec![100, 100]);
+    }
+
+    #[test]
+    fn truncates_at_min_font_size() {
+        //at 40, the 200 pixel box fits 10 characters including the ellipsis
+        let text = |truncate: Truncate| {
+            Text::default()
+                .text("abcdefghijklmnop")
+                .min_font_size(40)
+                .truncate(truncate)
+        };
+        let shown = |truncate: Truncate| drawn_lines(text(truncate))[0].0.clone();
+        assert_eq!(shown(Truncate::End), "abcdefghi\u{2026}");
+        assert_eq!(shown(Truncate::Start), "\u{2026}hijklmnop");
+        assert_eq!(shown(Truncate::Middle), "abcde\u{2026}mnop");
+        assert_eq!(text(Truncate::End).full_text(), "abcdefghijklmnop");
+
+        //lines that don't fit are left out, the last line shown ends with an ellipsis
+        let lines = drawn_lines(
+            Text::default()
+                .text("one two three four five")
+                .wrap(true)
+                .sizing(FontSizing::Fixed(40))
+                .truncate(Truncate::End),
+        );
+        let lines: Vec<&str> = lines.iter().map(|line| line.0.as_str()).collect();
+        assert_eq!(lines, vec!["one two", "three fou\u{2026}"]);
+    }
 }
@@ -0,0 +1,823 @@
+use super::event::Event;
+use super::font::FontHandle;
+use super::key::Key;
+use super::layout::{BuildContext, LayoutError};
+use super::render::Renderer;
+use super::theme::{Style, Theme};
+use super::{Directions2D, Geometry, Vector2, Widget};
+use macroquad::prelude::*;
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+///Callback invoked by a text input on itself. Can capture anything it needs from its environment.
+pub type Callback<T> = Box<dyn FnMut(&mut TextInput<T>)>;
+
+///Single line of editable text. Focus it by clicking it or moving keyboard focus to it, then type.
+///Shift with the arrow keys, Home and End selects text, Ctrl-A selects all of it.
+pub struct TextInput<T> {
+    //set by user
+    geometry: Geometry,
+    ///background color instead of the theme's
+    color: Option<Color>,
+    ///text and caret color instead of the theme's
+    text_color: Option<Color>,
+    ///radius of the corners of the background instead of the theme's
+    corner_radius: Option<f32>,
+    ///style taken from the View's theme in the last build
+    style: Style,
+    placeholder_color: Color,
+    selection_color: Color,
+    text: String,
+    ///shown in place of the text while it is empty
+    placeholder: String,
+    ///maximum number of characters, if limited
+    max_length: Option<usize>,
+    //callbacks are None only while they are running, see TextInput::call
+    //called after the text was edited
+    changed_callback: Option<Callback<T>>,
+    //called when Enter is pressed
+    submitted_callback: Option<Callback<T>>,
+
+    //indices below are counted in characters, not bytes
+    ///position of the caret, before the character at this index
+    caret: usize,
+    ///other end of the selection, the caret being one end. None if nothing is selected.
+    anchor: Option<usize>,
+    ///first visible character. Text before it was scrolled out of the box to keep the caret visible.
+    scroll: usize,
+    ///one past the last visible character
+    visible_end: usize,
+    ///width of the text before each character index, as measured by the last build
+    offsets: Vec<f32>,
+    ///font to draw with, or the theme's if None
+    font: Option<FontHandle>,
+    ///font the handle resolved to in the last build, None for macroquad's built in font
+    resolved: Option<Font>,
+    font_size: u16,
+    ///y coordinate of the text baseline
+    baseline: f32,
+    is_focused: bool,
+
+    ///should the widget be rebuilt?
+    build: bool,
+    pub id: u16,
+    ///identifies the widget within its View, see Widget::get_key
+    key: Option<Key>,
+    pub state: Rc<RefCell<T>>,
+}
+
+impl<T> TextInput<T> {
+    pub fn default(state: Rc<RefCell<T>>) -> Self {
+        TextInput {
+            geometry: Geometry::new(Vector2::new(100f32, 100f32)),
+            color: None,
+            text_color: None,
+            corner_radius: None,
+            style: Theme::default().text_input,
+            placeholder_color: GRAY,
+            selection_color: Color::new(0.4f32, 0.6f32, 1f32, 0.5f32),
+            text: String::new(),
+            placeholder: String::new(),
+            max_length: None,
+            changed_callback: Some(Box::new(|_: &mut TextInput<T>| {})),
+            submitted_callback: Some(Box::new(|_: &mut TextInput<T>| {})),
+            caret: 0,
+            anchor: None,
+            scroll: 0,
+            visible_end: 0,
+            offsets: vec![0f32],
+            font: None,
+            resolved: None,
+            font_size: 10,
+            baseline: 0f32,
+            is_focused: false,
+            build: false,
+            id: 0,
+            key: None,
+            state,
+        }
+    }
+
+    pub fn geometry(self, geometry: Geometry) -> Self {
+        TextInput { geometry, ..self }
+    }
+
+    pub fn color(self, color: Color) -> Self {
+        TextInput {
+            color: Some(color),
+            ..self
+        }
+    }
+
+    pub fn text_color(self, text_color: Color) -> Self {
+        TextInput {
+            text_color: Some(text_color),
+            ..self
+        }
+    }
+
+    pub fn corner_radius(self, corner_radius: f32) -> Self {
+        TextInput {
+            corner_radius: Some(corner_radius),
+            ..self
+        }
+    }
+
+    pub fn placeholder_color(self, placeholder_color: Color) -> Self {
+        TextInput {
+            placeholder_color,
+            ..self
+        }
+    }
+
+    pub fn selection_color(self, selection_color: Color) -> Self {
+        TextInput {
+            selection_color,
+            ..self
+        }
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.set_text(text);
+        self
+    }
+
+    pub fn placeholder(self, placeholder: &str) -> Self {
+        TextInput {
+            placeholder: String::from(placeholder),
+            ..self
+        }
+    }
+
+    ///Font from the View's font registry to draw the text with
+    pub fn font(self, font: FontHandle) -> Self {
+        TextInput {
+            font: Some(font),
+            ..self
+        }
+    }
+
+    ///Limit the text to max_length characters. Longer text is cut short.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.set_max_length(Some(max_length));
+        self
+    }
+
+    pub fn changed_callback<F: FnMut(&mut TextInput<T>) + 'static>(
+        self,
+        changed_callback: F,
+    ) -> Self {
+        TextInput {
+            changed_callback: Some(Box::new(changed_callback)),
+            ..self
+        }
+    }
+
+    pub fn submitted_callback<F: FnMut(&mut TextInput<T>) + 'static>(
+        self,
+        submitted_callback: F,
+    ) -> Self {
+        TextInput {
+            submitted_callback: Some(Box::new(submitted_callback)),
+            ..self
+        }
+    }
+
+    pub fn id(self, id: u16) -> Self {
+        Self { id, ..self }
+    }
+
+    ///Key identifying the widget within its View. Must be unique.
+    pub fn key<K: Into<Key>>(self, key: K) -> Self {
+        Self {
+            key: Some(key.into()),
+            ..self
+        }
+    }
+
+    ///Run the callback selected by slot. The callback is taken out of the input while it runs so that it can be
+    ///handed the input mutably, and put back afterwards unless it replaced itself.
+    fn call(&mut self, slot: fn(&mut TextInput<T>) -> &mut Option<Callback<T>>) {
+        if let Some(mut callback) = slot(self).take() {
+            callback(self);
+            let slot = slot(self);
+            if slot.is_none() {
+                *slot = Some(callback);
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    ///Byte index of the character at index
+    fn byte_index(&self, index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(index)
+            .map_or(self.text.len(), |(byte, _)| byte)
+    }
+
+    ///Remove the selected text and drop the selection. Returns false if nothing was selected.
+    fn delete_selection(&mut self) -> bool {
+        let selection = self.get_selection();
+        //an anchor left at the caret selects nothing, but would select what is typed next
+        self.anchor = None;
+        match selection {
+            Some((start, end)) => {
+                let range = self.byte_index(start)..self.byte_index(end);
+                self.text.replace_range(range, "");
+                self.caret = start;
+                true
+            }
+            None => false,
+        }
+    }
+
+    ///Replace the selection with character, or insert it at the caret
+    fn insert(&mut self, character: char) {
+        let deleted = self.delete_selection();
+        let fits = match self.max_length {
+            Some(max) => self.len() < max,
+            None => true,
+        };
+        if fits {
+            let index = self.byte_index(self.caret);
+            self.text.insert(index, character);
+            self.caret += 1;
+        } else if !deleted {
+            return;
+        }
+        self.changed();
+    }
+
+    ///Move the caret to index, extending the selection if select is true and dropping it otherwise
+    fn move_caret(&mut self, index: usize, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.caret);
+        } else {
+            self.anchor = None;
+        }
+        self.caret = index.min(self.len());
+        self.build = true;
+    }
+
+    ///The text was edited. Rebuild to scroll the caret into view and let the callback know.
+    fn changed(&mut self) {
+        self.build = true;
+        TextInput::call(self, |input| &mut input.changed_callback);
+    }
+
+    ///x coordinate of the boundary before the character at index, for indices that were measured by the last build
+    fn x_at(&self, index: usize) -> f32 {
+        let offset = |index: usize| self.offsets.get(index).copied().unwrap_or(0f32);
+        self.geometry.top_left.x + self.padding() + offset(index) - offset(self.scroll)
+    }
+
+    ///Index of the visible character boundary closest to x
+    fn index_at(&self, x: f32) -> usize {
+        (self.scroll..=self.visible_end.min(self.offsets.len() - 1))
+            .min_by(|a, b| {
+                (self.x_at(*a) - x)
+                    .abs()
+                    .total_cmp(&(self.x_at(*b) - x).abs())
+            })
+            .unwrap_or(0)
+    }
+
+    ///Space left between the edges of the box and the text
+    fn padding(&self) -> f32 {
+        self.geometry.abs_sides.y / 10f32
+    }
+
+    #[cfg(feature = "debug_draw")]
+    fn debug_draw(&self, renderer: &mut dyn Renderer) {
+        renderer.draw_rectangle_lines(
+            self.geometry.top_left.x - self.geometry.abs_margins.left,
+            self.geometry.top_left.y - self.geometry.abs_margins.top,
+            self.geometry.abs_sides.x
+                + self.geometry.abs_margins.left
+                + self.geometry.abs_margins.right,
+            self.geometry.abs_sides.y
+                + self.geometry.abs_margins.top
+                + self.geometry.abs_margins.bottom,
+            1.0,
+            MAGENTA,
+        );
+    }
+}
+
+impl<T: 'static> Widget for TextInput<T> {
+    fn draw(&self, renderer: &mut dyn Renderer) {
+        #[cfg(feature = "debug_draw")]
+        self.debug_draw(renderer);
+        renderer.draw_rounded_rectangle(
+            self.geometry.top_left.x,
+            self.geometry.top_left.y,
+            self.geometry.abs_sides.x,
+            self.geometry.abs_sides.y,
+            self.corner_radius.unwrap_or(self.style.corner_radius),
+            self.get_color(),
+        );
+        let height = self.geometry.abs_sides.y - 2f32 * self.padding();
+        let top = self.geometry.top_left.y + self.padding();
+        if self.is_focused {
+            if let Some((start, end)) = self.get_selection() {
+                let start = self.x_at(start.max(self.scroll));
+                let end = self.x_at(end.min(self.visible_end));
+                if end > start {
+                    renderer.draw_rectangle(start, top, end - start, height, self.selection_color);
+                }
+            }
+        }
+        //only the part of the text that fits in the box is drawn
+        let (text, color) = if self.text.is_empty() {
+            (
+                self.placeholder.chars().take(self.visible_end).collect(),
+                self.placeholder_color,
+            )
+        } else {
+            let visible: String = self
+                .text
+                .chars()
+                .skip(self.scroll)
+                .take(self.visible_end.saturating_sub(self.scroll))
+                .collect();
+            (visible, self.get_text_color())
+        };
+        if !text.is_empty() {
+            renderer.draw_text(
+                text.as_str(),
+                self.geometry.top_left.x + self.padding(),
+                self.baseline,
+                TextParams {
+                    font: self.resolved.unwrap_or_default(),
+                    font_size: self.font_size,
+                    color,
+                    ..TextParams::default()
+                },
+            );
+        }
+        if self.is_focused && self.caret >= self.scroll && self.caret <= self.visible_end {
+            let x = self.x_at(self.caret);
+            renderer.draw_line(x, top, x, top + height, 2f32, self.get_text_color());
+        }
+    }
+
+    fn build(
+        &mut self,
+        geometry: &Geometry,
+        margins: Option<Directions2D>,
+        ctx: &mut BuildContext,
+    ) -> Result<Vector2, LayoutError> {
+        let sides = self.geometry.validate(self.id, ctx)?;
+        self.build = false;
+        //Find original dimensions of parent and
+        //calculate dimensions of this widget from parent dimensions and positions from margins
+        let dimensions = Vector2 {
+            x: geometry.abs_sides.x * sides.x / 100f32,
+            y: geometry.abs_sides.y * sides.y / 100f32,
+        };
+        self.geometry.abs_sides = dimensions;
+        let margins = margins.unwrap_or(Directions2D {
+            top: geometry.abs_sides.y * self.geometry.margins.top / 100f32,
+            bottom: geometry.abs_sides.y * self.geometry.margins.bottom / 100f32,
+            left: geometry.abs_sides.x * self.geometry.margins.left / 100f32,
+            right: geometry.abs_sides.x * self.geometry.margins.right / 100f32,
+        });
+        self.geometry.abs_margins = margins;
+        self.geometry.top_left = Vector2 {
+            x: geometry.top_left_curr.x + margins.left,
+            y: geometry.top_left_curr.y + margins.top,
+        };
+
+        //the font fills the height of the box, less the padding, like Text fits its font to its box
+        let inner = Vector2::new(
+            dimensions.x - 2f32 * self.padding(),
+            dimensions.y - 2f32 * self.padding(),
+        );
+        let shown = if self.text.is_empty() {
+            self.placeholder.as_str()
+        } else {
+            self.text.as_str()
+        };
+        self.style = ctx.theme.text_input;
+        self.resolved = ctx.fonts.resolve(self.font.or(self.style.font), shown);
+        let line = ctx.renderer.measure_text("Ag", self.resolved, 1);
+        self.font_size = (inner.y / line.height) as u16;
+        let line = ctx
+            .renderer
+            .measure_text("Ag", self.resolved, self.font_size);
+        self.baseline =
+            self.geometry.top_left.y + (dimensions.y - line.height) / 2f32 + line.offset_y;
+
+        //measure the text before every character, to place the caret and find what fits in the box
+        let mut prefix = String::new();
+        self.offsets = vec![0f32];
+        for character in shown.chars() {
+            prefix.push(character);
+            self.offsets.push(
+                ctx.renderer
+                    .measure_text(prefix.as_str(), self.resolved, self.font_size)
+                    .width,
+            );
+        }
+        self.caret = self.caret.min(self.len());
+        let end = self.offsets.len() - 1;
+        let width = |from: usize, to: usize| self.offsets[to] - self.offsets[from];
+
+        //scroll just far enough for the caret to be visible, without leaving space after the end of the text
+        let mut scroll = self.scroll.min(self.caret);
+        while scroll < self.caret && width(scroll, self.caret) > inner.x {
+            scroll += 1;
+        }
+        while scroll > 0 && width(scroll - 1, end) <= inner.x {
+            scroll -= 1;
+        }
+        let mut visible_end = scroll;
+        while visible_end < end && width(scroll, visible_end + 1) <= inner.x {
+            visible_end += 1;
+        }
+        self.scroll = scroll;
+        self.visible_end = visible_end;
+
+        Ok(Vector2 {
+            x: geometry.top_left_curr.x + margins.left + dimensions.x + margins.right,
+            y: geometry.top_left_curr.y + margins.top + dimensions.y + margins.bottom,
+        })
+    }
+
+    fn tick(&mut self) {}
+
+    fn handle_event(&mut self, event: &Event) -> bool {
+        let (key, modifiers) = match *event {
+            //focus itself is given by View when the pointer is pressed
+            Event::PointerDown {
+                position,
+                button: MouseButton::Left,
+            } if self.geometry.contains(position) => {
+                let index = self.index_at(position.x);
+                self.move_caret(index, false);
+                return true;
+            }
+            _ if !self.is_focused => return false,
+            Event::Text(character) => {
+                self.insert(character);
+                return true;
+            }
+            Event::KeyDown { key, modifiers } => (key, modifiers),
+            _ => return false,
+        };
+        match key {
+            KeyCode::Left => match self.get_selection() {
+                Some((start, _)) if !modifiers.shift => self.move_caret(start, false),
+                _ => self.move_caret(self.caret.saturating_sub(1), modifiers.shift),
+            },
+            KeyCode::Right => match self.get_selection() {
+                Some((_, end)) if !modifiers.shift => self.move_caret(end, false),
+                _ => self.move_caret(self.caret + 1, modifiers.shift),
+            },
+            KeyCode::Home => self.move_caret(0, modifiers.shift),
+            KeyCode::End => self.move_caret(self.len(), modifiers.shift),
+            KeyCode::A if modifiers.ctrl => {
+                self.move_caret(0, false);
+                self.move_caret(self.len(), true);
+            }
+            KeyCode::Backspace => {
+                if self.delete_selection() {
+                    self.changed();
+                } else if self.caret > 0 {
+                    self.caret -= 1;
+                    let index = self.byte_index(self.caret);
+                    self.text.remove(index);
+                    self.changed();
+                }
+            }
+            KeyCode::Delete => {
+                if self.delete_selection() {
+                    self.changed();
+                } else if self.caret < self.len() {
+                    let index = self.byte_index(self.caret);
+                    self.text.remove(index);
+                    self.changed();
+                }
+            }
+            KeyCode::Enter | KeyCode::KpEnter => {
+                TextInput::call(self, |input| &mut input.submitted_callback);
+            }
+            //typed as text, but must not activate anything while typing
+            KeyCode::Space => {}
+            _ => return false,
+        }
+        true
+    }
+
+    fn get_side(&self) -> Vector2 {
+        self.geometry.sides
+    }
+
+    fn get_geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+
+    fn kind(&self) -> &'static str {
+        "text_input"
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.is_focused = focused;
+        if !focused {
+            self.anchor = None;
+        }
+    }
+
+    fn restore_state(&mut self, old: &dyn Widget) {
+        if let Some(old) = old.downcast_ref::<TextInput<T>>() {
+            self.set_text(old.text.as_str());
+            self.caret = old.caret.min(self.len());
+            self.anchor = old.anchor.map(|anchor| anchor.min(self.len()));
+            self.scroll = old.scroll;
+            self.is_focused = old.is_focused;
+        }
+    }
+
+    fn get_id(&self) -> u16 {
+        self.id
+    }
+
+    fn get_key(&self) -> Option<&Key> {
+        self.key.as_ref()
+    }
+
+    fn get_build(&self) -> bool {
+        self.build
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+//Setters and getters for TextInput
+impl<T> TextInput<T> {
+    ///Replace the text, cutting it short if it is longer than the maximum length. Doesn't call the changed callback.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = match self.max_length {
+            Some(max) => text.chars().take(max).collect(),
+            None => String::from(text),
+        };
+        self.caret = self.len();
+        self.anchor = None;
+        self.build = true;
+    }
+
+    pub fn set_placeholder(&mut self, placeholder: &str) {
+        self.placeholder = String::from(placeholder);
+        self.build = true;
+    }
+
+    pub fn set_max_length(&mut self, max_length: Option<usize>) {
+        self.max_length = max_length;
+        let text = self.text.clone();
+        self.set_text(text.as_str());
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = Some(color);
+    }
+
+    pub fn set_geometry(&mut self, geometry: Geometry) {
+        self.geometry = geometry;
+    }
+
+    pub fn set_changed_callback<F: FnMut(&mut TextInput<T>) + 'static>(
+        &mut self,
+        changed_callback: F,
+    ) {
+        self.changed_callback = Some(Box::new(changed_callback));
+    }
+
+    pub fn set_submitted_callback<F: FnMut(&mut TextInput<T>) + 'static>(
+        &mut self,
+        submitted_callback: F,
+    ) {
+        self.submitted_callback = Some(Box::new(submitted_callback));
+    }
+
+    pub fn get_text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    ///Background color, the input's own or the theme's
+    pub fn get_color(&self) -> Color {
+        self.color.unwrap_or(self.style.color)
+    }
+
+    ///Text color, the input's own or the theme's
+    pub fn get_text_color(&self) -> Color {
+        self.text_color.unwrap_or(self.style.text_color)
+    }
+
+    ///Position of the caret, in characters
+    pub fn get_caret(&self) -> usize {
+        self.caret
+    }
+
+    ///Start and end of the selected text in characters, if any is selected
+    pub fn get_selection(&self) -> Option<(usize, usize)> {
+        match self.anchor {
+            Some(anchor) if anchor != self.caret => {
+                Some((anchor.min(self.caret), anchor.max(self.caret)))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn get_is_focused(&self) -> bool {
+        self.is_focused
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::column::Column;
+    use crate::layout::LayoutPolicy;
+    use crate::render::{DrawCommand, RecordingRenderer};
+    use crate::View;
+    use macroquad::miniquad::KeyMods;
+
+    fn key(key: KeyCode, shift: bool) -> Event {
+        Event::KeyDown {
+            key,
+            modifiers: KeyMods {
+                shift,
+                ..KeyMods::default()
+            },
+        }
+    }
+
+    fn typed<T: 'static>(input: &mut TextInput<T>, text: &str) {
+        for character in text.chars() {
+            input.handle_event(&Event::Text(character));
+        }
+    }
+
+    //builds the input as 400x60 on an 800x600 screen, which fits 16 characters of its font
+    fn build<T: 'static>(input: &mut TextInput<T>) {
+        let renderer = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        let mut ctx = BuildContext::new(LayoutPolicy::Panic, &renderer);
+        let mut screen = Geometry::new(Vector2::new(100f32, 100f32));
+        screen.abs_sides = renderer.screen_size;
+        input.build(&screen, None, &mut ctx).unwrap();
+    }
+
+    fn drawn_text<T: 'static>(input: &TextInput<T>) -> Vec<(String, Color)> {
+        let mut renderer = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        input.draw(&mut renderer);
+        renderer
+            .commands
+            .into_iter()
+            .filter_map(|command| match command {
+                DrawCommand::Text { text, color, .. } => Some((text, color)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn input(state: Rc<RefCell<Vec<String>>>) -> TextInput<Vec<String>> {
+        TextInput::default(state)
+            .geometry(Geometry::new(Vector2::new(50f32, 10f32)))
+            .placeholder("Name")
+            .submitted_callback(|input: &mut TextInput<Vec<String>>| {
+                let text = String::from(input.get_text());
+                input.state.borrow_mut().push(text);
+                input.set_text("");
+            })
+    }
+
+    #[test]
+    fn edits_selection_and_submits() {
+        let state = Rc::new(RefCell::new(Vec::new()));
+        let mut input = input(state.clone()).max_length(8);
+        //typing does nothing until the input is focused
+        assert!(!input.handle_event(&Event::Text('x')));
+        input.set_focused(true);
+        typed(&mut input, "hello world");
+        assert_eq!(input.get_text(), "hello wo");
+        input.handle_event(&key(KeyCode::Left, false));
+        input.handle_event(&key(KeyCode::Left, true));
+        input.handle_event(&key(KeyCode::Left, true));
+        assert_eq!(input.get_selection(), Some((5, 7)));
+        typed(&mut input, "_");
+        assert_eq!(input.get_text(), "hello_o");
+        input.handle_event(&key(KeyCode::Backspace, false));
+        input.handle_event(&key(KeyCode::Home, false));
+        input.handle_event(&key(KeyCode::Delete, false));
+        assert_eq!(input.get_text(), "elloo");
+        //space is typed rather than activating anything
+        assert!(input.handle_event(&key(KeyCode::Space, false)));
+        input.handle_event(&key(KeyCode::Enter, false));
+        assert_eq!(*state.borrow(), vec![String::from("elloo")]);
+        assert_eq!(input.get_text(), "");
+    }
+
+    #[test]
+    fn deleting_past_the_ends_changes_nothing() {
+        let changed = Rc::new(RefCell::new(Vec::new()));
+        let mut input =
+            input(changed.clone()).changed_callback(|input: &mut TextInput<Vec<String>>| {
+                let text = String::from(input.get_text());
+                input.state.borrow_mut().push(text);
+            });
+        input.set_focused(true);
+        typed(&mut input, "ab");
+        input.handle_event(&key(KeyCode::Delete, false));
+        input.handle_event(&key(KeyCode::Home, false));
+        input.handle_event(&key(KeyCode::Backspace, false));
+        assert_eq!(input.get_text(), "ab");
+        assert_eq!(
+            *changed.borrow(),
+            vec![String::from("a"), String::from("ab")]
+        );
+        input.handle_event(&key(KeyCode::Delete, false));
+        assert_eq!(changed.borrow().last(), Some(&String::from("b")));
+    }
+
+    #[test]
+    fn selecting_back_to_the_caret_leaves_nothing_selected() {
+        let mut input = input(Rc::new(RefCell::new(Vec::new())));
+        input.set_focused(true);
+        typed(&mut input, "abc");
+        input.handle_event(&key(KeyCode::Left, true));
+        input.handle_event(&key(KeyCode::Right, true));
+        assert_eq!(input.get_selection(), None);
+        typed(&mut input, "xy");
+        assert_eq!(input.get_text(), "abcxy");
+        input.handle_event(&key(KeyCode::Left, true));
+        input.handle_event(&key(KeyCode::Right, true));
+        input.handle_event(&key(KeyCode::Backspace, false));
+        input.handle_event(&key(KeyCode::Backspace, false));
+        assert_eq!(input.get_text(), "abc");
+    }
+
+    #[test]
+    fn scrolls_to_keep_caret_visible() {
+        let mut input = input(Rc::new(RefCell::new(Vec::new())));
+        build(&mut input);
+        assert_eq!(drawn_text(&input), vec![(String::from("Name"), GRAY)]);
+
+        input.set_focused(true);
+        typed(&mut input, "abcdefghijklmnopqrstuvwxyz");
+        assert!(input.get_build());
+        build(&mut input);
+        assert_eq!(
+            drawn_text(&input),
+            vec![(String::from("klmnopqrstuvwxyz"), BLACK)]
+        );
+
+        input.handle_event(&key(KeyCode::Home, false));
+        build(&mut input);
+        assert_eq!(
+            drawn_text(&input),
+            vec![(String::from("abcdefghijklmnop"), BLACK)]
+        );
+    }
+
+    #[test]
+    fn clicking_focuses() {
+        let changed = Rc::new(RefCell::new(Vec::new()));
+        let mut view = View::new(Column::new().push(input(changed.clone()).changed_callback(
+            |input: &mut TextInput<Vec<String>>| {
+                let text = String::from(input.get_text());
+                input.state.borrow_mut().push(text);
+            },
+        )));
+        view.build_with(&RecordingRenderer::new(Vector2::new(800f32, 600f32)));
+        let click = |position: Vector2| Event::PointerDown {
+            position,
+            button: MouseButton::Left,
+        };
+
+        view.handle_event(&click(Vector2::new(400f32, 300f32)));
+        assert_eq!(view.focus().focused(), Some(&[0][..]));
+        view.handle_event(&Event::Text('a'));
+        assert_eq!(*changed.borrow(), vec![String::from("a")]);
+
+        //clicking elsewhere takes focus away
+        view.handle_event(&click(Vector2::new(10f32, 10f32)));
+        assert_eq!(view.focus().focused(), None);
+        view.handle_event(&Event::Text('b'));
+        assert_eq!(changed.borrow().len(), 1);
+    }
+}
@@ -0,0 +1,193 @@
+/*
+Themes give widgets their colors, fonts and corner radius, so that they don't all have to be set on every widget. A
+View has a Theme, which holds a Style for each kind of widget that draws with one. Widgets take their style from the
+theme every time the tree is built, so changing the View's theme, for example to switch between dark and light, restyles
+the whole tree. Properties set on a widget itself, like Button::color, override its style.
+
+Texts inside a button are drawn with the button's text color and font instead of the text style.
+*/
+
+use super::font::FontHandle;
+use macroquad::prelude::*;
+
+///Look of one kind of widget. Not every widget uses every property, Text only draws with text_color and font.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Style {
+    ///fill of the widget
+    pub color: Color,
+    ///fill while the pointer is over the widget
+    pub hovered: Color,
+    ///fill while the widget is being pressed
+    pub pressed: Color,
+    ///fill while the widget is disabled
+    pub disabled: Color,
+    pub text_color: Color,
+    ///font to draw text with, or the font registry's default if None
+    pub font: Option<FontHandle>,
+    ///radius of the corners of the fill, 0 for square corners
+    pub corner_radius: f32,
+}
+
+impl Style {
+    ///Style of a single color in every state
+    pub const fn plain(color: Color, text_color: Color) -> Self {
+        Style {
+            color,
+            hovered: color,
+            pressed: color,
+            disabled: color,
+            text_color,
+            font: None,
+            corner_radius: 0f32,
+        }
+    }
+}
+
+///Styles of every kind of widget in a View
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    pub button: Style,
+    pub text: Style,
+    pub text_input: Style,
+}
+
+impl Default for Theme {
+    ///Plain white widgets with square corners. Buttons turn gray while pressed or disabled.
+    fn default() -> Self {
+        Theme {
+            button: Style {
+                pressed: LIGHTGRAY,
+                disabled: GRAY,
+                ..Style::plain(WHITE, WHITE)
+            },
+            text: Style::plain(WHITE, WHITE),
+            text_input: Style::plain(WHITE, BLACK),
+        }
+    }
+}
+
+impl Theme {
+    ///Light text on dark, rounded widgets
+    pub fn dark() -> Self {
+        let button = Style {
+            color: Color::new(0.25f32, 0.27f32, 0.3f32, 1f32),
+            hovered: Color::new(0.33f32, 0.36f32, 0.4f32, 1f32),
+            pressed: Color::new(0.18f32, 0.2f32, 0.22f32, 1f32),
+            disabled: Color::new(0.2f32, 0.2f32, 0.2f32, 1f32),
+            text_color: Color::new(0.92f32, 0.92f32, 0.92f32, 1f32),
+            font: None,
+            corner_radius: 8f32,
+        };
+        Theme {
+            button,
+            text: Style::plain(button.color, button.text_color),
+            text_input: Style {
+                color: Color::new(0.12f32, 0.13f32, 0.15f32, 1f32),
+                ..button
+            },
+        }
+    }
+
+    ///Dark text on light, rounded widgets
+    pub fn light() -> Self {
+        let button = Style {
+            color: Color::new(0.9f32, 0.9f32, 0.92f32, 1f32),
+            hovered: Color::new(0.82f32, 0.84f32, 0.88f32, 1f32),
+            pressed: Color::new(0.7f32, 0.73f32, 0.78f32, 1f32),
+            disabled: Color::new(0.94f32, 0.94f32, 0.94f32, 1f32),
+            text_color: Color::new(0.1f32, 0.1f32, 0.12f32, 1f32),
+            font: None,
+            corner_radius: 8f32,
+        };
+        Theme {
+            button,
+            text: Style::plain(button.color, button.text_color),
+            text_input: Style {
+                color: WHITE,
+                ..button
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Theme;
+    use crate::button::Button;
+    use crate::column::Column;
+    use crate::event::Event;
+    use crate::render::{DrawCommand, RecordingRenderer};
+    use crate::text::Text;
+    use crate::{Geometry, Vector2, View};
+    use macroquad::prelude::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn menu() -> View {
+        let button = |key: &str| {
+            Button::default(Rc::new(RefCell::new(())))
+                .geometry(Geometry::new(Vector2::from(100, 40)))
+                .key(key)
+                .child(Text::default().text("play"))
+        };
+        View::new(
+            Column::new()
+                .push(button("themed"))
+                .push(button("red").color(RED))
+                .push(
+                    Text::default()
+                        .text("title")
+                        .geometry(Geometry::new(Vector2::from(100, 20))),
+                ),
+        )
+    }
+
+    //fill of the rectangle and color of the texts drawn, in drawing order
+    fn drawn(view: &View) -> (Vec<Color>, Vec<Color>) {
+        let mut renderer = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        view.draw_with(&mut renderer);
+        let mut fills = Vec::new();
+        let mut texts = Vec::new();
+        for command in renderer.commands {
+            match command {
+                DrawCommand::Rectangle { color, .. } if !fills.contains(&color) => {
+                    fills.push(color)
+                }
+                DrawCommand::Text { color, .. } => texts.push(color),
+                _ => (),
+            }
+        }
+        (fills, texts)
+    }
+
+    #[test]
+    fn widgets_follow_the_theme_unless_overridden() {
+        let renderer = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        let mut view = menu();
+        view.build_with(&renderer);
+        assert_eq!(drawn(&view), (vec![WHITE, RED], vec![WHITE; 3]));
+
+        let dark = Theme::dark();
+        view.set_theme(dark);
+        view.build_with(&renderer);
+        let (fills, texts) = drawn(&view);
+        assert_eq!(fills, vec![dark.button.color, RED]);
+        assert_eq!(texts, vec![dark.button.text_color; 3]);
+        let themed = view.find_by_key("themed").unwrap();
+        let themed = themed.downcast_ref::<Button<()>>().unwrap();
+        assert_eq!(themed.get_color(), dark.button.color);
+
+        //hovering picks the theme's hovered color, but not over a color of the button's own
+        view.handle_event(&Event::PointerMove {
+            position: Vector2::new(400f32, 100f32),
+        });
+        let themed = view.find_by_key("themed").unwrap();
+        let themed = themed.downcast_ref::<Button<()>>().unwrap();
+        assert_eq!(themed.get_color(), dark.button.hovered);
+        view.handle_event(&Event::PointerMove {
+            position: Vector2::new(400f32, 300f32),
+        });
+        let red = view.find_by_key("red").unwrap();
+        assert_eq!(red.downcast_ref::<Button<()>>().unwrap().get_color(), RED);
+    }
+}
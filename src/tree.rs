@@ -0,0 +1,247 @@
+/*
+Walking widget trees from outside. Every widget exposes its children through Widget::child and Widget::child_mut, so
+game logic can find a widget by its id, downcast it to its concrete type and change it in place, instead of building
+the whole tree again. Widgets are visited in drawing order: a widget before its children, and earlier children before
+later ones. Each is passed with its path, the indices of the children leading to it from the root.
+
+Widgets can also be found by key. Those without one are keyed by their path spelled out with the kinds of their
+ancestors, like column/2/row/1, which stays the same as long as the tree keeps its shape.
+*/
+
+use super::focus::{widget_at, widget_at_mut};
+use super::key::Key;
+use super::layout::{BuildContext, LayoutError};
+use super::Widget;
+use std::collections::HashMap;
+
+///Call visitor on root and every widget under it, in drawing order, with its path from root
+pub fn visit(root: &dyn Widget, visitor: &mut dyn FnMut(&[usize], &dyn Widget)) {
+    fn walk(
+        widget: &dyn Widget,
+        path: &mut Vec<usize>,
+        visitor: &mut dyn FnMut(&[usize], &dyn Widget),
+    ) {
+        visitor(path, widget);
+        for index in 0..widget.child_count() {
+            if let Some(child) = widget.child(index) {
+                path.push(index);
+                walk(child, path, visitor);
+                path.pop();
+            }
+        }
+    }
+    walk(root, &mut Vec::new(), visitor);
+}
+
+///Call visitor on root and every widget under it mutably, in drawing order, with its path from root. Children are
+///visited after the visitor is done with their parent, so it may change them.
+pub fn visit_mut(root: &mut dyn Widget, visitor: &mut dyn FnMut(&[usize], &mut dyn Widget)) {
+    fn walk(
+        widget: &mut dyn Widget,
+        path: &mut Vec<usize>,
+        visitor: &mut dyn FnMut(&[usize], &mut dyn Widget),
+    ) {
+        visitor(path, widget);
+        for index in 0..widget.child_count() {
+            if let Some(child) = widget.child_mut(index) {
+                path.push(index);
+                walk(child, path, visitor);
+                path.pop();
+            }
+        }
+    }
+    walk(root, &mut Vec::new(), visitor);
+}
+
+///Path from root to the first widget in drawing order with the given id
+pub fn path_of(root: &dyn Widget, id: u16) -> Option<Vec<usize>> {
+    let mut found = None;
+    visit(root, &mut |path, widget| {
+        if found.is_none() && widget.get_id() == id {
+            found = Some(path.to_vec());
+        }
+    });
+    found
+}
+
+///First widget under root in drawing order with the given id, root included. Ids default to 0, so give the widgets
+///you look up ids of their own.
+pub fn find_by_id(root: &dyn Widget, id: u16) -> Option<&dyn Widget> {
+    let path = path_of(root, id)?;
+    widget_at(root, &path)
+}
+
+///First widget under root in drawing order with the given id, mutably
+pub fn find_by_id_mut(root: &mut dyn Widget, id: u16) -> Option<&mut dyn Widget> {
+    let path = path_of(root, id)?;
+    widget_at_mut(root, &path)
+}
+
+///Key of root and every widget under it, in drawing order, with its path from root. Widgets without a key get a
+///Key::Path.
+pub fn keys(root: &dyn Widget) -> Vec<(Vec<usize>, Key)> {
+    fn walk(
+        widget: &dyn Widget,
+        path: &mut Vec<usize>,
+        auto: &str,
+        keys: &mut Vec<(Vec<usize>, Key)>,
+    ) {
+        let key = match widget.get_key() {
+            Some(key) => key.clone(),
+            None => Key::Path(String::from(auto)),
+        };
+        keys.push((path.clone(), key));
+        for index in 0..widget.child_count() {
+            if let Some(child) = widget.child(index) {
+                let auto = match auto {
+                    "" => format!("{}/{}", widget.kind(), index),
+                    _ => format!("{}/{}/{}", auto, widget.kind(), index),
+                };
+                path.push(index);
+                walk(child, path, auto.as_str(), keys);
+                path.pop();
+            }
+        }
+    }
+    let mut keys = Vec::new();
+    walk(root, &mut Vec::new(), "", &mut keys);
+    keys
+}
+
+///Report every key used by more than one widget under root to ctx
+pub fn check_keys(root: &dyn Widget, ctx: &mut BuildContext) -> Result<(), LayoutError> {
+    let mut seen: HashMap<Key, Vec<usize>> = HashMap::new();
+    for (path, key) in keys(root) {
+        match seen.get(&key) {
+            Some(first) => ctx.report(LayoutError::DuplicateKey {
+                key,
+                first: first.clone(),
+                second: path,
+            })?,
+            None => {
+                seen.insert(key, path);
+            }
+        }
+    }
+    Ok(())
+}
+
+///Widget under root with the given key, root included
+pub fn find_by_key<'a>(root: &'a dyn Widget, key: &Key) -> Option<&'a dyn Widget> {
+    let (path, _) = keys(root).into_iter().find(|(_, found)| found == key)?;
+    widget_at(root, &path)
+}
+
+///Widget under root with the given key mutably
+pub fn find_by_key_mut<'a>(root: &'a mut dyn Widget, key: &Key) -> Option<&'a mut dyn Widget> {
+    let (path, _) = keys(root).into_iter().find(|(_, found)| found == key)?;
+    widget_at_mut(root, &path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{keys, path_of};
+    use crate::button::Button;
+    use crate::column::Column;
+    use crate::key::Key;
+    use crate::layout::{LayoutError, LayoutPolicy};
+    use crate::render::RecordingRenderer;
+    use crate::row::Row;
+    use crate::text::Text;
+    use crate::{Geometry, Vector2, View};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn finds_and_changes_widgets_by_id() {
+        let mut view = View::new(
+            Column::new()
+                .push(
+                    Row::new()
+                        .geometry(Geometry::new(Vector2::from(100, 50)))
+                        .push(
+                            Text::default()
+                                .text("score: 0")
+                                .geometry(Geometry::new(Vector2::from(100, 100)))
+                                .id(3),
+                        ),
+                )
+                .push(
+                    Button::default(Rc::new(RefCell::new(0u32)))
+                        .geometry(Geometry::new(Vector2::from(100, 50)))
+                        .id(5),
+                ),
+        );
+        view.build_with(&RecordingRenderer::new(Vector2::new(800f32, 600f32)));
+
+        let mut kinds = Vec::new();
+        view.visit(&mut |path, widget| kinds.push((path.to_vec(), widget.kind())));
+        assert_eq!(
+            kinds,
+            vec![
+                (vec![], "column"),
+                (vec![0], "row"),
+                (vec![0, 0], "text"),
+                (vec![1], "button"),
+            ]
+        );
+
+        let text = view.find_by_id_mut(3).unwrap();
+        text.downcast_mut::<Text>().unwrap().set_text("score: 1");
+        assert!(text.downcast_mut::<Button<u32>>().is_none());
+        assert!(view.root().get_build());
+        let button = view.find_by_id(5).unwrap().downcast_ref::<Button<u32>>();
+        assert_eq!(*button.unwrap().state.borrow(), 0);
+        assert!(view.find_by_id(7).is_none());
+        assert_eq!(path_of(view.root(), 5), Some(vec![1]));
+
+        view.build_with(&RecordingRenderer::new(Vector2::new(800f32, 600f32)));
+        assert!(!view.root().get_build());
+        let text = view.find_by_id(3).unwrap().downcast_ref::<Text>();
+        assert_eq!(text.unwrap().full_text(), "score: 1");
+    }
+
+    #[derive(Debug)]
+    enum Slot {
+        Score,
+    }
+
+    fn labels(first: Key, second: Key) -> View {
+        let label = |key: Key| {
+            Text::default()
+                .geometry(Geometry::new(Vector2::from(50, 100)))
+                .key(key)
+        };
+        View::new(Column::new().push(Row::new().push(label(first)).push(label(second))))
+    }
+
+    #[test]
+    fn keys_are_unique_and_default_to_paths() {
+        let renderer = RecordingRenderer::new(Vector2::new(800f32, 600f32));
+        let mut view = labels(Key::from("name"), Key::typed(Slot::Score));
+        view.build_with(&renderer);
+        let found = keys(view.root());
+        assert_eq!(found[0].1, Key::Path(String::new()));
+        assert_eq!(found[1].1, Key::Path(String::from("column/0")));
+        assert_eq!(found[3], (vec![0, 1], Key::typed(Slot::Score)));
+        let score = view.find_by_key(Key::typed(Slot::Score)).unwrap();
+        assert_eq!(score.get_key(), Some(&Key::typed(Slot::Score)));
+        let row = view.find_by_key(Key::Path(String::from("column/0")));
+        assert_eq!(row.unwrap().kind(), "row");
+        //names never match paths, or typed keys that print the same
+        assert!(view.find_by_key("column/0").is_none());
+        assert!(view.find_by_key("Score").is_none());
+
+        let mut view = labels(Key::from("name"), Key::from("name"));
+        let duplicate = LayoutError::DuplicateKey {
+            key: Key::from("name"),
+            first: vec![0, 0],
+            second: vec![0, 1],
+        };
+        assert_eq!(view.try_build_with(&renderer), Err(duplicate.clone()));
+        let mut view =
+            labels(Key::from("name"), Key::from("name")).layout_policy(LayoutPolicy::Clamp);
+        assert_eq!(view.try_build_with(&renderer), Ok(()));
+        assert_eq!(view.layout_errors(), &[duplicate]);
+    }
+}
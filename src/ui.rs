@@ -0,0 +1,148 @@
+/*
+The ui! macro writes widget trees as nested braces instead of chains of builder calls. Each widget is its kind,
+arguments for its constructor where it needs them, optional properties in square brackets, and its children in braces:
+
+    ui! {
+        column {
+            button(state.clone()) [size(90, 40), color(WHITE), id(1), is_pressed_callback(|button| { ... })] {
+                text("Start") [size(90, 90), color(RED)]
+            }
+        }
+    }
+
+The kinds are column, row, button(state) and text("..."). size(x, y) sets the geometry from percentages of the parent,
+every other property calls the builder method of the same name, so ids, keys and callbacks are written like they are
+with the builders. Sizes must be constants: the heights of a column's children and the widths of a row's children are
+added up when compiling, and more than 100% is a compile error instead of a LayoutError::Overflow at runtime. Widgets
+without a size take up 100%.
+*/
+
+///Build a widget tree from nested braces, see the ui module.
+///
+///Children that need more than 100% of their parent don't compile:
+///
+///```compile_fail
+///use mcgooey::ui;
+///let state = std::rc::Rc::new(std::cell::RefCell::new(()));
+///let column = ui! {
+///    column {
+///        button(state.clone()) [size(100, 60)]
+///        button(state.clone()) [size(100, 60)]
+///    }
+///};
+///```
+#[macro_export]
+macro_rules! ui {
+    (column $([$($prop:tt)*])? {
+        $($kind:ident $(($($arg:expr),*))? $([$($child_prop:tt)*])? $({$($child:tt)*})?)*
+    }) => {{
+        const _: () = assert!(
+            0 $(+ $crate::__ui_size!($($($child_prop)*)?).1)* <= 100,
+            "children of a column are more than 100% tall"
+        );
+        $crate::__ui_props!($crate::column::Column::new(); $($($prop)*)?)
+            $(.push($crate::ui!($kind $(($($arg),*))? $([$($child_prop)*])? $({$($child)*})?)))*
+    }};
+    (row $([$($prop:tt)*])? {
+        $($kind:ident $(($($arg:expr),*))? $([$($child_prop:tt)*])? $({$($child:tt)*})?)*
+    }) => {{
+        const _: () = assert!(
+            0 $(+ $crate::__ui_size!($($($child_prop)*)?).0)* <= 100,
+            "children of a row are more than 100% wide"
+        );
+        $crate::__ui_props!($crate::row::Row::new(); $($($prop)*)?)
+            $(.push($crate::ui!($kind $(($($arg),*))? $([$($child_prop)*])? $({$($child)*})?)))*
+    }};
+    (button($state:expr) $([$($prop:tt)*])? $({
+        $kind:ident $(($($arg:expr),*))? $([$($child_prop:tt)*])? $({$($child:tt)*})?
+    })?) => {
+        $crate::__ui_props!($crate::button::Button::default($state); $($($prop)*)?)
+            $(.child($crate::ui!($kind $(($($arg),*))? $([$($child_prop)*])? $({$($child)*})?)))?
+    };
+    (text($text:expr) $([$($prop:tt)*])?) => {
+        $crate::__ui_props!($crate::text::Text::default().text($text); $($($prop)*)?)
+    };
+}
+
+///Apply the properties of a ui! widget to its builder
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ui_props {
+    ($widget:expr;) => {
+        $widget
+    };
+    ($widget:expr; size($x:expr, $y:expr) $(, $($rest:tt)*)?) => {
+        $crate::__ui_props!(
+            $widget.geometry($crate::Geometry::new($crate::Vector2::from($x, $y)));
+            $($($rest)*)?
+        )
+    };
+    ($widget:expr; $method:ident($($value:tt)*) $(, $($rest:tt)*)?) => {
+        $crate::__ui_props!($widget.$method($($value)*); $($($rest)*)?)
+    };
+}
+
+///Size in percent given to a ui! widget by its properties, as a constant (width, height)
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ui_size {
+    () => {
+        (100, 100)
+    };
+    (size($x:expr, $y:expr) $(, $($rest:tt)*)?) => {
+        ($x, $y)
+    };
+    ($method:ident($($value:tt)*) $(, $($rest:tt)*)?) => {
+        $crate::__ui_size!($($($rest)*)?)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::button::Button;
+    use crate::render::RecordingRenderer;
+    use crate::{Vector2, View};
+    use macroquad::prelude::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    const ROW_HEIGHT: i32 = 40;
+
+    #[test]
+    fn builds_nested_widgets() {
+        let state = Rc::new(RefCell::new(0));
+        let mut view = View::new(ui! {
+            column {
+                row [size(100, ROW_HEIGHT), id(1)] {
+                    text("left") [size(50, 100), color(RED)]
+                    text("right") [size(50, 100), key("right")]
+                }
+                button(state.clone()) [
+                    size(90, 60),
+                    is_pressed_callback(|button: &mut Button<i32>| *button.state.borrow_mut() += 1),
+                ] {
+                    text("press")
+                }
+            }
+        });
+        view.build_with(&RecordingRenderer::new(Vector2::new(800f32, 600f32)));
+
+        let mut widgets = Vec::new();
+        view.visit(&mut |path, widget| {
+            widgets.push((path.to_vec(), widget.kind(), widget.get_side()))
+        });
+        assert_eq!(widgets.len(), 6);
+        assert_eq!(widgets[1], (vec![0], "row", Vector2::from(100, 40)));
+        assert_eq!(widgets[3], (vec![0, 1], "text", Vector2::from(50, 100)));
+        assert_eq!(widgets[4], (vec![1], "button", Vector2::from(90, 60)));
+        assert_eq!(widgets[5], (vec![1, 0], "text", Vector2::from(100, 100)));
+        assert_eq!(view.find_by_id(1).unwrap().kind(), "row");
+        assert!(view.find_by_key("right").is_some());
+
+        view.find_by_id_mut(0)
+            .and_then(|widget| widget.child_mut(1))
+            .unwrap()
+            .activate();
+        assert_eq!(*state.borrow(), 1);
+    }
+}